@@ -1,2 +1,2 @@
 #!/usr/bin/env bash
-RUST_BACKTRACE=1 cargo run --release --verbose --bin day_$1 < fixtures/day_$1.in
+RUST_BACKTRACE=1 cargo run --release --verbose --bin aoc -- "$@"
@@ -0,0 +1,157 @@
+//! The correct answers of every day, kept next to the inputs as
+//! `fixtures/day_NN.ans`, so that changes to a solution can be checked
+//! against them.
+//!
+//! An answers file looks like the runner's output without timings:
+//!
+//! ```text
+//! part_1: 454
+//! part_2: 566
+//! ```
+//!
+//! Answers that span several lines start on the line after their label.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use errors::*;
+use solution::{Answers, Day};
+
+/// The input of day `number` in `fixtures`
+pub fn input_path(fixtures: &Path, number: usize) -> PathBuf {
+    fixtures.join(format!("day_{:02}.in", number))
+}
+
+/// The answers file of day `number` in `fixtures`
+pub fn path(fixtures: &Path, number: usize) -> PathBuf {
+    fixtures.join(format!("day_{:02}.ans", number))
+}
+
+/// The answers to both parts of a day, without trailing whitespace
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Known {
+    pub part_1: String,
+    pub part_2: String,
+}
+
+impl Known {
+    pub fn new(part_1: &str, part_2: &str) -> Known {
+        Known {
+            part_1: part_1.trim_end().to_owned(),
+            part_2: part_2.trim_end().to_owned(),
+        }
+    }
+
+    pub fn of(answers: &Answers) -> Known {
+        Known::new(&answers.part_1.value, &answers.part_2.value)
+    }
+
+    pub fn parse(data: &str) -> Result<Known> {
+        let mut parts = vec![];
+        for line in data.lines() {
+            let label = format!("part_{}:", parts.len() + 1);
+            if parts.len() < 2 && line.starts_with(&label) {
+                parts.push(line[label.len()..].trim_start().to_owned());
+            } else if let Some(part) = parts.last_mut() {
+                if !part.is_empty() {
+                    part.push('\n');
+                }
+                part.push_str(line);
+            } else {
+                bail!("answers have to start with part_1:");
+            }
+        }
+        match parts.as_slice() {
+            [part_1, part_2] => Ok(Known::new(part_1, part_2)),
+            _ => bail!("expected answers to part_1 and part_2"),
+        }
+    }
+
+    /// The answers recorded at `path`, or `None` if nothing is recorded
+    pub fn load(path: &Path) -> Result<Option<Known>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let data = fs::read_to_string(path)?;
+        Known::parse(&data)
+            .map(Some)
+            .chain_err(|| format!("unable to parse {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_string())
+            .chain_err(|| format!("unable to write {}", path.display()))
+    }
+
+    /// The parts of `self` that `other` does not match, with the answer from
+    /// `other`
+    pub fn mismatches<'a>(&'a self, other: &'a Known) -> Vec<(usize, &'a str, &'a str)> {
+        let mut result = vec![];
+        if self.part_1 != other.part_1 {
+            result.push((1, self.part_1.as_str(), other.part_1.as_str()));
+        }
+        if self.part_2 != other.part_2 {
+            result.push((2, self.part_2.as_str(), other.part_2.as_str()));
+        }
+        result
+    }
+}
+
+impl fmt::Display for Known {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (label, answer) in &[("part_1", &self.part_1), ("part_2", &self.part_2)] {
+            if answer.contains('\n') {
+                writeln!(f, "{}:\n{}", label, answer)?;
+            } else if answer.is_empty() {
+                writeln!(f, "{}:", label)?;
+            } else {
+                writeln!(f, "{}: {}", label, answer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Solves `day` over its input in `fixtures` and fails unless both answers
+/// match the recorded ones
+pub fn check(day: &Day, fixtures: &Path) -> Result<()> {
+    let known = match Known::load(&path(fixtures, day.number))? {
+        Some(known) => known,
+        None => bail!("no answers are recorded for day {}", day.number),
+    };
+    let input = input_path(fixtures, day.number);
+    let data =
+        fs::read_to_string(&input).chain_err(|| format!("unable to read {}", input.display()))?;
+    let found = Known::of(&(day.solve)(&data)?);
+    if let Some(&(part, expected, actual)) = known.mismatches(&found).first() {
+        bail!(
+            "day {} part {} is now {:?}, but was recorded as {:?}",
+            day.number,
+            part,
+            actual,
+            expected
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Known;
+
+    #[test]
+    fn test_known() {
+        let known = Known::new("#..\n.#.\n", "");
+        assert_eq!("part_1:\n#..\n.#.\npart_2:\n", known.to_string());
+        assert_eq!(known, Known::parse(&known.to_string()).unwrap());
+
+        let known = Known::parse("part_1: 454\npart_2: 566\n").unwrap();
+        assert_eq!(Known::new("454", "566"), known);
+        let found = Known::new("454", "567");
+        assert_eq!(vec![(2, "566", "567")], known.mismatches(&found));
+
+        assert!(Known::parse("454\n").is_err());
+        assert!(Known::parse("part_1: 454\n").is_err());
+    }
+}
@@ -0,0 +1,177 @@
+//! Measuring how long each step of a day takes and how much it allocates.
+//!
+//! Allocations are only counted in binaries that install `Counting` as their
+//! global allocator:
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static ALLOCATOR: Counting = Counting;
+//! ```
+//!
+//! Everywhere else they read as zero.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Write;
+use std::ops::Sub;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use errors::*;
+use solution::Day;
+
+static COUNT: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// The system allocator, counting every allocation and the bytes asked for
+pub struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+fn count(bytes: usize) {
+    COUNT.fetch_add(1, Ordering::Relaxed);
+    BYTES.fetch_add(bytes as u64, Ordering::Relaxed);
+}
+
+/// A number of allocations, counting reallocations, and the bytes they asked
+/// for
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct Allocations {
+    pub count: u64,
+    pub bytes: u64,
+}
+
+impl Allocations {
+    /// The allocations made so far by the whole program
+    pub fn so_far() -> Allocations {
+        Allocations {
+            count: COUNT.load(Ordering::Relaxed),
+            bytes: BYTES.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Sub for Allocations {
+    type Output = Allocations;
+
+    fn sub(self, other: Allocations) -> Allocations {
+        Allocations {
+            count: self.count - other.count,
+            bytes: self.bytes - other.bytes,
+        }
+    }
+}
+
+/// One step of a day measured over several runs
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub day: usize,
+    pub name: &'static str,
+    pub times: Vec<Duration>,
+    /// Allocations made by the first run
+    pub allocations: Allocations,
+}
+
+impl Step {
+    pub fn min(&self) -> Duration {
+        self.times.iter().cloned().min().unwrap_or_default()
+    }
+
+    pub fn median(&self) -> Duration {
+        let mut times = self.times.clone();
+        times.sort();
+        times.get(times.len() / 2).cloned().unwrap_or_default()
+    }
+}
+
+/// Solves `day` over `data` `runs` times, and measures parsing and both parts
+pub fn measure(day: &Day, data: &str, runs: usize) -> Result<Vec<Step>> {
+    let mut steps: Vec<Step> = vec![];
+    for run in 0..runs.max(1) {
+        let answers = (day.solve)(data)?;
+        let measured = [
+            ("parse", answers.parse.time, answers.parse.allocations),
+            ("part_1", answers.part_1.time, answers.part_1.allocations),
+            ("part_2", answers.part_2.time, answers.part_2.allocations),
+        ];
+        for (i, &(name, time, allocations)) in measured.iter().enumerate() {
+            if run == 0 {
+                steps.push(Step {
+                    day: day.number,
+                    name,
+                    times: vec![],
+                    allocations,
+                });
+            }
+            steps[i].times.push(time);
+        }
+    }
+    Ok(steps)
+}
+
+/// The steps as tab separated values with a header, one step per line, so
+/// that reports of two commits can be compared with diff
+pub fn report(steps: &[Step]) -> String {
+    let mut result = String::from("day\tstep\truns\tmedian_ns\tmin_ns\tallocations\tbytes\n");
+    for step in steps {
+        writeln!(
+            result,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            step.day,
+            step.name,
+            step.times.len(),
+            step.median().as_nanos(),
+            step.min().as_nanos(),
+            step.allocations.count,
+            step.allocations.bytes
+        )
+        .unwrap();
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{report, Allocations, Step};
+    use std::time::Duration;
+
+    #[test]
+    fn test_report() {
+        let step = Step {
+            day: 9,
+            name: "part_2",
+            times: vec![5, 1, 3]
+                .into_iter()
+                .map(Duration::from_nanos)
+                .collect(),
+            allocations: Allocations {
+                count: 2,
+                bytes: 64,
+            },
+        };
+        assert_eq!(Duration::from_nanos(3), step.median());
+        assert_eq!(Duration::from_nanos(1), step.min());
+        assert_eq!(
+            "day\tstep\truns\tmedian_ns\tmin_ns\tallocations\tbytes\n9\tpart_2\t3\t3\t1\t2\t64\n",
+            report(&[step])
+        );
+    }
+}
@@ -0,0 +1,202 @@
+extern crate aoc2018;
+#[macro_use]
+extern crate error_chain;
+
+use aoc2018::answers::{self, Known};
+use aoc2018::bench::{self, Counting};
+use aoc2018::days;
+use aoc2018::errors::*;
+use aoc2018::solution::Day;
+use std::path::Path;
+use std::time::Duration;
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+const USAGE: &str = "usage: aoc DAYS [OPTIONS]
+
+Solves DAYS with their inputs from fixtures/day_NN.in, where DAYS is a day
+like 7, a range like 1-10, or all. Answers that differ from the ones recorded
+in fixtures/day_NN.ans are marked, and make the run fail.
+
+OPTIONS:
+  --save-answers  record the answers in fixtures/day_NN.ans
+  --bench         time parsing and both parts over several runs, and count
+                  their allocations, instead of printing answers
+  --runs N        run every day N times when benchmarking (default 5)
+  --report PATH   benchmark, and write the measurements to PATH as tab
+                  separated values
+
+Other options are passed on to the days, for extra output like pictures:
+  --record PATH   record the simulation of day 13, 15 or 18 as a GIF or frames
+  --image PATH    save a picture of the grid of day 10, 13, 17, 18 or 20
+  --drift         show how the frequency of day 1 drifts until it repeats
+  --ansi          show the grid of day 10, 13, 15, 18 or 20 in colour";
+
+quick_main!(run);
+
+fn run() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let spec = match args.first() {
+        Some(spec) if spec != "-h" && spec != "--help" => spec,
+        _ => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+    };
+    let selected = select(spec)?;
+    let mut options = args[1..].to_vec();
+    let save_answers = take_flag(&mut options, "--save-answers");
+    let report = take_value(&mut options, "--report")?;
+    let runs = match take_value(&mut options, "--runs")? {
+        Some(runs) => runs.parse().chain_err(|| "--runs takes a number")?,
+        None => 5,
+    };
+    let benchmark = take_flag(&mut options, "--bench") || report.is_some();
+    if benchmark && save_answers {
+        bail!("benchmarks do not save answers, run without --bench to save them");
+    }
+    if benchmark && !options.is_empty() {
+        bail!("benchmarks do not take {}\n\n{}", options.join(" "), USAGE);
+    }
+
+    let mut failed = 0;
+    let mut steps = vec![];
+    for day in selected {
+        let result = if benchmark {
+            bench_day(&day, runs).map(|measured| steps.extend(measured))
+        } else {
+            run_day(&day, save_answers, &options)
+        };
+        if let Err(e) = result {
+            failed += 1;
+            eprintln!("Error: {}", e);
+            for cause in e.iter().skip(1) {
+                eprintln!("Caused by: {}", cause);
+            }
+        }
+    }
+    if let Some(path) = report {
+        std::fs::write(&path, bench::report(&steps))
+            .chain_err(|| format!("unable to write {}", path))?;
+    }
+    if failed > 0 {
+        bail!("{} of the days failed", failed);
+    }
+    Ok(())
+}
+
+/// Removes `flag` from `options`, and tells whether it was there
+fn take_flag(options: &mut Vec<String>, flag: &str) -> bool {
+    let found = options.iter().any(|option| option == flag);
+    options.retain(|option| option != flag);
+    found
+}
+
+/// Removes `flag` and the value after it from `options`, and returns the
+/// value
+fn take_value(options: &mut Vec<String>, flag: &str) -> Result<Option<String>> {
+    match options.iter().position(|option| option == flag) {
+        None => Ok(None),
+        Some(i) if i + 1 < options.len() => {
+            let value = options.remove(i + 1);
+            options.remove(i);
+            Ok(Some(value))
+        }
+        Some(_) => bail!("{} needs a value", flag),
+    }
+}
+
+/// The days picked by a command line argument like `7`, `1-10` or `all`
+fn select(spec: &str) -> Result<Vec<Day>> {
+    let all = days::all();
+    if spec == "all" {
+        return Ok(all);
+    }
+    let number = |s: &str| -> Result<usize> {
+        s.parse()
+            .chain_err(|| format!("invalid day '{}'\n\n{}", s, USAGE))
+    };
+    let (first, last) = match spec.find('-') {
+        Some(i) => (number(&spec[..i])?, number(&spec[i + 1..])?),
+        None => (number(spec)?, number(spec)?),
+    };
+    let selected: Vec<Day> = all
+        .into_iter()
+        .filter(|day| first <= day.number && day.number <= last)
+        .collect();
+    if selected.is_empty() {
+        bail!("there are no days in {}", spec);
+    }
+    Ok(selected)
+}
+
+fn read_input(day: &Day) -> Result<String> {
+    let path = answers::input_path(Path::new("fixtures"), day.number);
+    std::fs::read_to_string(&path).chain_err(|| format!("unable to read {}", path.display()))
+}
+
+fn run_day(day: &Day, save_answers: bool, options: &[String]) -> Result<()> {
+    let data = read_input(day)?;
+    let answers = (day.solve)(&data).chain_err(|| format!("day {} failed", day.number))?;
+    println!(
+        "== day {:02} (parsed in {})",
+        day.number,
+        millis(answers.parse.time)
+    );
+    for (name, part) in &[("part_1", &answers.part_1), ("part_2", &answers.part_2)] {
+        let separator = if part.value.contains('\n') { "\n" } else { " " };
+        println!(
+            "{} ({}):{}{}",
+            name,
+            millis(part.time),
+            separator,
+            part.value.trim_end()
+        );
+    }
+
+    let found = Known::of(&answers);
+    let answers_path = answers::path(Path::new("fixtures"), day.number);
+    if save_answers {
+        found.save(&answers_path)?;
+        println!("saved to {}", answers_path.display());
+    } else if let Some(known) = Known::load(&answers_path)? {
+        let mismatches = known.mismatches(&found);
+        for &(part, expected, _) in mismatches.iter() {
+            println!(
+                "part_{} is wrong, the recorded answer is {}",
+                part, expected
+            );
+        }
+        if !mismatches.is_empty() {
+            bail!("day {} has wrong answers", day.number);
+        }
+    }
+
+    if !options.is_empty() {
+        (day.extras)(&data, options).chain_err(|| format!("day {} failed", day.number))?;
+    }
+    Ok(())
+}
+
+fn bench_day(day: &Day, runs: usize) -> Result<Vec<bench::Step>> {
+    let data = read_input(day)?;
+    let steps =
+        bench::measure(day, &data, runs).chain_err(|| format!("day {} failed", day.number))?;
+    for step in steps.iter() {
+        println!(
+            "day {:02} {:<6} median {:>12} min {:>12} {:>10} allocations {:>12} bytes",
+            step.day,
+            step.name,
+            millis(step.median()),
+            millis(step.min()),
+            step.allocations.count,
+            step.allocations.bytes
+        );
+    }
+    Ok(steps)
+}
+
+fn millis(time: Duration) -> String {
+    format!("{:.2} ms", time.as_secs_f64() * 1000.0)
+}
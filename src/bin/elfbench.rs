@@ -0,0 +1,56 @@
+extern crate aoc2018;
+
+use aoc2018::elfcode::{Arithmetic, Interpreter, Machine};
+use std::time::{Duration, Instant};
+
+const PROGRAMS: [(&str, &str); 2] = [
+    ("day_19", include_str!("../../fixtures/day_19.in")),
+    ("day_21", include_str!("../../fixtures/day_21.in")),
+];
+
+/// Compares `Machine::next` against `Interpreter` on the puzzle inputs, over
+/// the same number of instructions (default 100 million, or the first
+/// argument). Both start from zeroed registers and use wrapping arithmetic,
+/// so they must end up in the same state.
+fn main() {
+    let budget = std::env::args()
+        .nth(1)
+        .map(|arg| arg.parse().expect("usage: elfbench [INSTRUCTIONS]"))
+        .unwrap_or(100_000_000);
+    for &(name, data) in PROGRAMS.iter() {
+        let mut machine: Machine = data.parse().unwrap();
+        machine.arithmetic = Arithmetic::Wrapping;
+        let mut fast = Interpreter::new(&machine).unwrap();
+
+        let start = Instant::now();
+        let mut executed = 0;
+        while executed < budget && machine.next().unwrap() {
+            executed += 1;
+        }
+        let slow_time = start.elapsed();
+
+        let start = Instant::now();
+        fast.run(executed);
+        let fast_time = start.elapsed();
+
+        assert_eq!(machine.registers, fast.registers, "{} diverged", name);
+        println!(
+            "{}: {} instructions, Machine::next {:.3}s ({:.1} M/s), Interpreter {:.3}s ({:.1} M/s), {:.1}x",
+            name,
+            executed,
+            seconds(slow_time),
+            rate(executed, slow_time),
+            seconds(fast_time),
+            rate(executed, fast_time),
+            seconds(slow_time) / seconds(fast_time)
+        );
+    }
+}
+
+fn seconds(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + duration.subsec_nanos() as f64 * 1e-9
+}
+
+fn rate(executed: usize, duration: Duration) -> f64 {
+    executed as f64 / seconds(duration) / 1e6
+}
@@ -3,9 +3,49 @@ extern crate aoc2018;
 use aoc2018::elfcode;
 use std::io::Read;
 
+const USAGE: &str = "usage: elfcc [--flat | --profile [--budget N] [--r0 N]] < PROGRAM";
+
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let flag = |name: &str| args.iter().any(|arg| arg == name);
+    let value = |name: &str| {
+        args.iter()
+            .position(|arg| arg == name)
+            .map(|i| args.get(i + 1).and_then(|v| v.parse().ok()).expect(USAGE))
+    };
     let mut data = String::new();
     std::io::stdin().read_to_string(&mut data).unwrap();
-    let machine: elfcode::Machine = data.parse().unwrap();
-    print!("{}", machine.deparse());
-}
\ No newline at end of file
+    let mut machine: elfcode::Machine = data.parse().unwrap();
+    if flag("--profile") {
+        machine.registers[0] = value("--r0").unwrap_or(0);
+        machine.enable_profiling();
+        let budget = value("--budget").unwrap_or(10_000_000);
+        match machine.run_until(budget, |_| false) {
+            Ok(stop) => println!("; {}", stop),
+            Err(e) => println!("; fault: {}", e),
+        }
+        print!("{}", machine.annotate());
+        print_registers(&machine);
+    } else if flag("--flat") {
+        print!("{}", machine.deparse());
+    } else {
+        print!("{}", machine.decompile());
+    }
+}
+
+fn print_registers(machine: &elfcode::Machine) {
+    let profile = machine.profile.as_ref().unwrap();
+    for register in 0..machine.registers.len() {
+        let common: Vec<String> = profile
+            .most_written(register, 5)
+            .iter()
+            .map(|&(value, count)| format!("{} ({}x)", value, count))
+            .collect();
+        println!(
+            "; r[{}]: {} distinct values written, most often {}",
+            register,
+            profile.writes[register].len(),
+            common.join(", ")
+        );
+    }
+}
@@ -0,0 +1,134 @@
+extern crate aoc2018;
+
+use aoc2018::elfcode::{Arithmetic, Debugger, Machine, Stop};
+use std::io::{BufRead, Write};
+
+const HELP: &str = "commands:
+  break N | delete N    set or remove a breakpoint on instruction N
+  watch R | unwatch R   stop when register R changes
+  step [N]              execute N instructions (default 1)
+  continue [BUDGET]     run until a breakpoint, watchpoint or halt
+  set R V               set register R to V
+  mode checked|wrapping|saturating
+                        select how additions and multiplications overflow
+  regs                  print the registers
+  list                  print the program, marking the next instruction
+  trace                 toggle printing every executed instruction
+  quit";
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .expect("usage: elfdbg PROGRAM < COMMANDS");
+    let data = std::fs::read_to_string(&path).unwrap();
+    let machine: Machine = data.parse().unwrap();
+    let mut debugger = Debugger::new(machine);
+    let stdin = std::io::stdin();
+    prompt();
+    for line in stdin.lock().lines() {
+        let line = line.expect("Unable to read command");
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if words.is_empty() {
+            prompt();
+            continue;
+        }
+        let arg = |i: usize| words.get(i).and_then(|w| w.parse::<usize>().ok());
+        match words[0] {
+            "b" | "break" => match arg(1) {
+                Some(n) => {
+                    debugger.breakpoints.insert(n);
+                }
+                None => println!("usage: break N"),
+            },
+            "d" | "delete" => match arg(1) {
+                Some(n) => {
+                    debugger.breakpoints.remove(&n);
+                }
+                None => println!("usage: delete N"),
+            },
+            "w" | "watch" => match arg(1) {
+                Some(r) => {
+                    debugger.watchpoints.insert(r);
+                }
+                None => println!("usage: watch R"),
+            },
+            "unwatch" => match arg(1) {
+                Some(r) => {
+                    debugger.watchpoints.remove(&r);
+                }
+                None => println!("usage: unwatch R"),
+            },
+            "s" | "step" => {
+                for _ in 0..arg(1).unwrap_or(1) {
+                    match debugger.step() {
+                        Ok(None) => (),
+                        Ok(Some(stop)) => {
+                            println!("{}", stop);
+                            if stop == Stop::Halted {
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            println!("fault: {}", e);
+                            break;
+                        }
+                    }
+                }
+                print_registers(&debugger);
+            }
+            "c" | "continue" => {
+                match debugger.run(arg(1).unwrap_or(usize::MAX)) {
+                    Ok(stop) => println!("{}", stop),
+                    Err(e) => println!("fault: {}", e),
+                }
+                print_registers(&debugger);
+            }
+            "set" => match (arg(1), arg(2)) {
+                (Some(r), Some(v)) if r < debugger.machine.registers.len() => {
+                    debugger.machine.registers[r] = v;
+                }
+                _ => println!("usage: set R V"),
+            },
+            "mode" => match words.get(1).cloned() {
+                Some("checked") => debugger.machine.arithmetic = Arithmetic::Checked,
+                Some("wrapping") => debugger.machine.arithmetic = Arithmetic::Wrapping,
+                Some("saturating") => debugger.machine.arithmetic = Arithmetic::Saturating,
+                _ => println!("usage: mode checked|wrapping|saturating"),
+            },
+            "r" | "regs" => print_registers(&debugger),
+            "l" | "list" => {
+                let current = debugger.machine.line();
+                for (n, listing) in debugger.machine.deparse().lines().enumerate() {
+                    let marker = if n == current { "=>" } else { "  " };
+                    println!("{} {}", marker, listing);
+                }
+            }
+            "t" | "trace" => {
+                if debugger.is_tracing() {
+                    debugger.clear_trace();
+                    println!("tracing off");
+                } else {
+                    debugger.set_trace(|step| println!("{}", step));
+                    println!("tracing on");
+                }
+            }
+            "q" | "quit" => break,
+            _ => println!("{}", HELP),
+        }
+        prompt();
+    }
+}
+
+fn prompt() {
+    print!("(elfdbg) ");
+    std::io::stdout().flush().unwrap();
+}
+
+fn print_registers(debugger: &Debugger) {
+    println!(
+        "ip={} executed={} registers={:?}",
+        debugger.machine.line(),
+        debugger.executed,
+        debugger.machine.registers
+    );
+}
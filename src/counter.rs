@@ -0,0 +1,144 @@
+//! Counting how often values occur.
+
+use std::collections::hash_map;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::iter::FromIterator;
+
+/// A multiset, as a count for every value seen at least once
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Counter<T: Eq + Hash> {
+    counts: HashMap<T, u64>,
+}
+
+impl<T: Eq + Hash> Default for Counter<T> {
+    fn default() -> Counter<T> {
+        Counter::new()
+    }
+}
+
+impl<T: Eq + Hash> Counter<T> {
+    pub fn new() -> Counter<T> {
+        Counter {
+            counts: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, value: T) {
+        self.add_n(value, 1);
+    }
+
+    pub fn add_n(&mut self, value: T, n: u64) {
+        if n > 0 {
+            *self.counts.entry(value).or_insert(0) += n;
+        }
+    }
+
+    /// How often `value` was seen, which is 0 for values never seen
+    pub fn get(&self, value: &T) -> u64 {
+        self.counts.get(value).cloned().unwrap_or(0)
+    }
+
+    /// The number of distinct values
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The sum of all counts
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Every distinct value with its count, in no particular order
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.counts.iter())
+    }
+
+    /// The counts by value
+    pub fn into_map(self) -> HashMap<T, u64> {
+        self.counts
+    }
+
+    /// Adds the counts of `other` to these
+    pub fn merge(&mut self, other: Counter<T>) {
+        for (value, n) in other.counts {
+            self.add_n(value, n);
+        }
+    }
+}
+
+impl<T: Eq + Hash + Ord> Counter<T> {
+    /// The `n` most common values with their counts, most common first and
+    /// equally common values in increasing order
+    pub fn most_common(&self, n: usize) -> Vec<(&T, u64)> {
+        let mut all: Vec<_> = self.iter().collect();
+        all.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        all.truncate(n);
+        all
+    }
+
+    /// The most common value with its count, the smallest one if several are
+    /// as common, or `None` if nothing was counted
+    pub fn argmax(&self) -> Option<(&T, u64)> {
+        self.iter()
+            .min_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)))
+    }
+}
+
+/// The values of a `Counter` with their counts
+pub struct Iter<'a, T: 'a>(hash_map::Iter<'a, T, u64>);
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (&'a T, u64);
+
+    fn next(&mut self) -> Option<(&'a T, u64)> {
+        self.0.next().map(|(value, &n)| (value, n))
+    }
+}
+
+impl<T: Eq + Hash> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Counter<T> {
+        let mut counter = Counter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Eq + Hash> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.add(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Counter;
+
+    #[test]
+    fn test_counter() {
+        let mut counter: Counter<char> = "abracadabra".chars().collect();
+        assert_eq!(5, counter.get(&'a'));
+        assert_eq!(0, counter.get(&'z'));
+        assert_eq!((5, 11), (counter.len(), counter.total()));
+        // b and r are tied, and b comes first
+        assert_eq!(
+            vec![(&'a', 5), (&'b', 2), (&'r', 2)],
+            counter.most_common(3)
+        );
+        assert_eq!(5, counter.most_common(10).len());
+
+        counter.merge("rrrrbbbc".chars().collect());
+        assert_eq!(Some((&'r', 6)), counter.argmax());
+        counter.add('a');
+        counter.add_n('z', 0);
+        assert_eq!(Some((&'a', 6)), counter.argmax());
+        assert_eq!(5, counter.len());
+        assert_eq!(None, Counter::<char>::new().argmax());
+    }
+}
@@ -0,0 +1,215 @@
+//! Fast-forwarding simulations that eventually repeat.
+//!
+//! All functions iterate `step` from an initial state, giving the sequence
+//! `x_0, x_1 = step(x_0), x_2 = step(x_1), ...`, and work out where it goes
+//! after `n` steps from the first repeated state instead of running all of
+//! them.
+
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Where a sequence starts repeating: `x_(start + length) == x_start`
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The smallest `i` with `x_i == x_n`
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Finds the cycle with Brent's algorithm, which keeps only two states at a
+/// time, at the price of stepping about three times as far as the first
+/// repeat.
+pub fn brent<T, F>(initial: T, mut step: F) -> Cycle
+where
+    T: Clone + Eq,
+    F: FnMut(&T) -> T,
+{
+    // find the length by moving the tortoise to the hare at every power of two
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+    // then walk two states `length` apart until they meet
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+/// The state after `n` steps. Remembers every state until one repeats.
+pub fn nth<T, F>(initial: T, step: F, n: usize) -> T
+where
+    T: Clone + Eq + Hash,
+    F: FnMut(&T) -> T,
+{
+    nth_by_key(initial, step, n, |state| state.clone())
+}
+
+/// Like `nth`, but only remembers a 64-bit hash of every state, and so
+/// assumes that different states seen before the first repeat have different
+/// hashes.
+pub fn nth_hashed<T, F>(initial: T, step: F, n: usize) -> T
+where
+    T: Hash,
+    F: FnMut(&T) -> T,
+{
+    nth_by_key(initial, step, n, hash_of)
+}
+
+fn hash_of<T: Hash>(state: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    state.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The state after `n` steps, where states with the same `key` are taken to
+/// be the same
+pub fn nth_by_key<T, K, F, G>(initial: T, mut step: F, n: usize, mut key: G) -> T
+where
+    K: Eq + Hash,
+    F: FnMut(&T) -> T,
+    G: FnMut(&T) -> K,
+{
+    let mut seen = HashMap::new();
+    let mut state = initial;
+    for i in 0..n {
+        match seen.entry(key(&state)) {
+            Entry::Occupied(entry) => {
+                let length = i - *entry.get();
+                for _ in 0..(n - i) % length {
+                    state = step(&state);
+                }
+                return state;
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(i);
+            }
+        }
+        state = step(&state);
+    }
+    state
+}
+
+/// `value` of the state after `n` steps, for simulations that repeat up to
+/// some drift, like a pattern that keeps its shape while it moves. Once a
+/// `key` repeats, `value` is assumed to change by the same amount over every
+/// further cycle.
+pub fn nth_value_drifting<T, K, F, G, V>(
+    initial: T,
+    mut step: F,
+    n: usize,
+    mut key: G,
+    mut value: V,
+) -> i64
+where
+    K: Eq + Hash,
+    F: FnMut(&T) -> T,
+    G: FnMut(&T) -> K,
+    V: FnMut(&T) -> i64,
+{
+    let mut seen = HashMap::new();
+    let mut state = initial;
+    for i in 0..n {
+        let current = value(&state);
+        match seen.entry(key(&state)) {
+            Entry::Occupied(entry) => {
+                let (first, first_value) = *entry.get();
+                let length = i - first;
+                let cycles = ((n - i) / length) as i64;
+                for _ in 0..(n - i) % length {
+                    state = step(&state);
+                }
+                return value(&state) + cycles * (current - first_value);
+            }
+            Entry::Vacant(entry) => {
+                entry.insert((i, current));
+            }
+        }
+        state = step(&state);
+    }
+    value(&state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{brent, nth, nth_hashed, nth_value_drifting, Cycle};
+
+    // 0, 1, 4, 25, 13, 1, 4, ... repeats from 1 with length 4
+    fn step(x: &u64) -> u64 {
+        (x + 1) * (x + 1) % 39
+    }
+
+    #[test]
+    fn test_brent() {
+        let cycle = brent(0, step);
+        assert_eq!(
+            Cycle {
+                start: 1,
+                length: 4
+            },
+            cycle
+        );
+        let sequence = [0, 1, 4, 25, 13, 1, 4, 25, 13, 1];
+        for n in 0..sequence.len() {
+            assert_eq!(sequence[n], sequence[cycle.reduce(n)]);
+            assert_eq!(sequence[n], nth(0, step, n));
+            assert_eq!(sequence[n], nth_hashed(0, step, n));
+        }
+        let big = 1_000_000_000_002;
+        assert_eq!(2, cycle.reduce(big));
+        assert_eq!(4, nth(0, step, big));
+        assert_eq!(4, nth_hashed(0, step, big));
+    }
+
+    #[test]
+    fn test_drifting() {
+        // a glider of period 2 that moves one cell right every other step
+        let step = |&(phase, position): &(u8, i64)| {
+            if phase == 0 {
+                (1, position)
+            } else {
+                (0, position + 1)
+            }
+        };
+        let key = |&(phase, _): &(u8, i64)| phase;
+        let value = |&(_, position): &(u8, i64)| position;
+        for n in 0..10 {
+            assert_eq!(
+                (n / 2) as i64,
+                nth_value_drifting((0, 0), step, n, key, value)
+            );
+        }
+        assert_eq!(
+            500_000_000_000,
+            nth_value_drifting((0, 0), step, 1_000_000_000_001, key, value)
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! The solutions of every day.
+
+use solution::Day;
+
+pub mod day_01;
+pub mod day_02;
+pub mod day_03;
+pub mod day_04;
+pub mod day_05;
+pub mod day_06;
+pub mod day_07;
+pub mod day_08;
+pub mod day_09;
+pub mod day_10;
+pub mod day_11;
+pub mod day_12;
+pub mod day_13;
+pub mod day_14;
+pub mod day_15;
+pub mod day_16;
+pub mod day_17;
+pub mod day_18;
+pub mod day_19;
+pub mod day_20;
+pub mod day_21;
+pub mod day_22;
+pub mod day_23;
+pub mod day_24;
+pub mod day_25;
+
+/// Every day, in order
+pub fn all() -> Vec<Day> {
+    vec![
+        Day::of::<day_01::Day01>(1),
+        Day::of::<day_02::Day02>(2),
+        Day::of::<day_03::Day03>(3),
+        Day::of::<day_04::Day04>(4),
+        Day::of::<day_05::Day05>(5),
+        Day::of::<day_06::Day06>(6),
+        Day::of::<day_07::Day07>(7),
+        Day::of::<day_08::Day08>(8),
+        Day::of::<day_09::Day09>(9),
+        Day::of::<day_10::Day10>(10),
+        Day::of::<day_11::Day11>(11),
+        Day::of::<day_12::Day12>(12),
+        Day::of::<day_13::Day13>(13),
+        Day::of::<day_14::Day14>(14),
+        Day::of::<day_15::Day15>(15),
+        Day::of::<day_16::Day16>(16),
+        Day::of::<day_17::Day17>(17),
+        Day::of::<day_18::Day18>(18),
+        Day::of::<day_19::Day19>(19),
+        Day::of::<day_20::Day20>(20),
+        Day::of::<day_21::Day21>(21),
+        Day::of::<day_22::Day22>(22),
+        Day::of::<day_23::Day23>(23),
+        Day::of::<day_24::Day24>(24),
+        Day::of::<day_25::Day25>(25),
+    ]
+}
+
+/// The day numbered `number`
+pub fn get(number: usize) -> Option<Day> {
+    all().into_iter().find(|day| day.number == number)
+}
@@ -0,0 +1,162 @@
+use errors::*;
+use parse;
+use solution::Solution;
+use std::collections::HashMap;
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<i64>;
+
+    fn parse(data: &str) -> Result<Vec<i64>> {
+        parse_changes(data)
+    }
+
+    fn part_1(changes: &Vec<i64>) -> Result<String> {
+        Ok(part_1(changes).to_string())
+    }
+
+    fn part_2(changes: &Vec<i64>) -> Result<String> {
+        Ok(part_2(changes)?.to_string())
+    }
+
+    fn extras(changes: &Vec<i64>, args: &[String]) -> Result<()> {
+        // optionally tell how the frequency drifts until it repeats
+        if args.iter().any(|arg| arg == "--drift") {
+            println!("drift per pass: {}", part_1(changes));
+            match first_repeat(changes) {
+                Some(repeat) => println!(
+                    "{} repeats after {} full passes and {} changes",
+                    repeat.frequency, repeat.passes, repeat.changes
+                ),
+                None => println!("no frequency ever repeats"),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_changes(data: &str) -> Result<Vec<i64>> {
+    parse::lines(data, |line| parse::value(data, line))
+}
+
+fn part_1(data: &Vec<i64>) -> i64 {
+    data.iter().sum()
+}
+
+fn part_2(data: &Vec<i64>) -> Result<i64> {
+    match first_repeat(data) {
+        Some(repeat) => Ok(repeat.frequency),
+        None => bail!(
+            "the frequency drifts by {} every pass and never repeats",
+            part_1(data)
+        ),
+    }
+}
+
+/// The first frequency reached twice while applying the changes over and
+/// over
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct Repeat {
+    frequency: i64,
+    /// Passes over all changes completed before the repeat
+    passes: u64,
+    /// Changes applied before the repeat, counting those of earlier passes
+    changes: u64,
+}
+
+/// Finds the first repeated frequency without simulating the passes.
+///
+/// After `k` passes the frequency following the first `j` changes is
+/// `sums[j] + k * drift`, where `sums` are the frequencies of the first pass
+/// and `drift` their total. Unless the first pass already repeats itself, a
+/// frequency can only come back if it is `sums[i]` for another `i` that
+/// leaves the same remainder modulo the drift and lies ahead of it in the
+/// direction of the drift. Sorting the frequencies by remainder puts each
+/// one next to the closest such `sums[i]`. Returns `None` if no frequency
+/// ever repeats.
+fn first_repeat(changes: &[i64]) -> Option<Repeat> {
+    let n = changes.len();
+    if n == 0 {
+        return Some(Repeat {
+            frequency: 0,
+            passes: 0,
+            changes: 0,
+        });
+    }
+    let mut sums = Vec::with_capacity(n);
+    let mut seen = HashMap::new();
+    let mut frequency = 0;
+    for (j, change) in changes.iter().enumerate() {
+        if seen.insert(frequency, j).is_some() {
+            return Some(Repeat {
+                frequency,
+                passes: 0,
+                changes: j as u64,
+            });
+        }
+        sums.push(frequency);
+        frequency += change;
+    }
+    let drift = frequency;
+    if drift == 0 {
+        return Some(Repeat {
+            frequency: 0,
+            passes: 1,
+            changes: n as u64,
+        });
+    }
+
+    let step = drift.abs();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by_key(|&j| (sums[j].rem_euclid(step), sums[j] * drift.signum()));
+    // the repeat comes after `passes` passes and `j` more changes
+    let mut best: Option<(u64, usize)> = None;
+    for pair in order.windows(2) {
+        let (j, i) = (pair[0], pair[1]);
+        if sums[j].rem_euclid(step) != sums[i].rem_euclid(step) {
+            continue;
+        }
+        let passes = ((sums[i] - sums[j]) / drift) as u64;
+        if best.map_or(true, |best| (passes, j) < best) {
+            best = Some((passes, j));
+        }
+    }
+    best.map(|(passes, j)| Repeat {
+        frequency: sums[j] + passes as i64 * drift,
+        passes,
+        changes: passes * n as u64 + j as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{first_repeat, part_2, Repeat};
+
+    #[test]
+    fn test_part_2() {
+        assert_eq!(0, part_2(&vec![1, -1]).unwrap());
+        assert_eq!(2, part_2(&vec![1, -2, 3, 1]).unwrap());
+        assert_eq!(10, part_2(&vec![3, 3, 4, -2, -4]).unwrap());
+        assert_eq!(5, part_2(&vec![-6, 3, 8, 5, -6]).unwrap());
+        assert_eq!(14, part_2(&vec![7, 7, -2, -7, -4]).unwrap());
+        assert!(part_2(&vec![1, 2]).is_err());
+    }
+
+    #[test]
+    fn test_first_repeat() {
+        let repeat = Repeat {
+            frequency: 2,
+            passes: 1,
+            changes: 6,
+        };
+        assert_eq!(Some(repeat), first_repeat(&[1, -2, 3, 1]));
+        let repeat = Repeat {
+            frequency: -1,
+            passes: 0,
+            changes: 3,
+        };
+        assert_eq!(Some(repeat), first_repeat(&[-1, 2, -2, 5]));
+        assert_eq!(10, first_repeat(&[5, -4]).unwrap().changes);
+    }
+}
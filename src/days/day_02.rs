@@ -0,0 +1,237 @@
+use errors::*;
+use solution::Solution;
+use std::collections::{BTreeMap, HashMap};
+use Counter;
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<String>;
+
+    fn parse(data: &str) -> Result<Vec<String>> {
+        Ok(data.lines().map(str::to_owned).collect())
+    }
+
+    fn part_1(ids: &Vec<String>) -> Result<String> {
+        Ok(part_1(ids).to_string())
+    }
+
+    fn part_2(ids: &Vec<String>) -> Result<String> {
+        part_2(ids)
+    }
+}
+
+fn part_1(data: &Vec<String>) -> u64 {
+    let mut has_2: u64 = 0;
+    let mut has_3: u64 = 0;
+    for id in data {
+        let counts: Counter<char> = id.chars().collect();
+        if counts.iter().any(|(_, count)| count == 2) {
+            has_2 += 1;
+        }
+        if counts.iter().any(|(_, count)| count == 3) {
+            has_3 += 1;
+        }
+    }
+    return has_2 * has_3;
+}
+
+fn part_2(data: &Vec<String>) -> Result<String> {
+    match pairs_at_distance(data, 1).into_iter().next() {
+        Some(pair) => Ok(pair.common),
+        None => bail!("no two IDs differ in exactly one position"),
+    }
+}
+
+/// Two IDs, by their index, that differ in some positions, and the letters
+/// in the other positions
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Pair {
+    pub first: usize,
+    pub second: usize,
+    pub common: String,
+}
+
+/// Every pair of equally long IDs that differ in exactly `k` positions,
+/// ordered by their indices. Positions are bytes, which for box IDs are
+/// letters.
+///
+/// Rather than comparing every pair, each choice of `k` positions in turn is
+/// left out of every ID, and IDs that become equal are sorted together. Two
+/// IDs at distance `k` become equal when exactly the positions they differ
+/// in are left out. IDs are keyed by a polynomial hash, from which a choice
+/// of positions is subtracted without copying the ID, and candidates are
+/// compared to rule out collisions. Copies of the same ID are handled once
+/// and only paired up at the end, so that many copies do not make every
+/// choice of positions quadratic.
+pub fn pairs_at_distance(ids: &[String], k: usize) -> Vec<Pair> {
+    let mut copies: HashMap<&[u8], Vec<usize>> = HashMap::new();
+    for (index, id) in ids.iter().enumerate() {
+        copies.entry(id.as_bytes()).or_default().push(index);
+    }
+    let mut by_length: BTreeMap<usize, Vec<&[u8]>> = BTreeMap::new();
+    for &id in copies.keys() {
+        by_length.entry(id.len()).or_default().push(id);
+    }
+
+    // pairs of distinct IDs, by their index in their group, and the letters
+    // they have in common
+    let mut pairs = vec![];
+    let mut keys: Vec<(u64, usize)> = vec![];
+    for (&length, group) in by_length.iter() {
+        if k > length {
+            continue;
+        }
+        let weights: Vec<u64> = (0..length as u32)
+            .map(|i| 0x0100_0000_01b3u64.wrapping_pow(i + 1))
+            .collect();
+        let hashes: Vec<u64> = group
+            .iter()
+            .map(|id| {
+                id.iter().zip(weights.iter()).fold(0u64, |h, (&b, &w)| {
+                    h.wrapping_add(u64::from(b).wrapping_mul(w))
+                })
+            })
+            .collect();
+        // the positions left out, in increasing order
+        let mut masked: Vec<usize> = (0..k).collect();
+        loop {
+            keys.clear();
+            for (i, id) in group.iter().enumerate() {
+                let key = masked.iter().fold(hashes[i], |h, &position| {
+                    h.wrapping_sub(u64::from(id[position]).wrapping_mul(weights[position]))
+                });
+                keys.push((key, i));
+            }
+            // IDs with equal keys end up next to each other
+            keys.sort_unstable();
+            let mut start = 0;
+            while start < keys.len() {
+                let end = start
+                    + keys[start..]
+                        .iter()
+                        .take_while(|&&(key, _)| key == keys[start].0)
+                        .count();
+                for (n, &(_, a)) in keys[start..end].iter().enumerate() {
+                    for &(_, b) in keys[n + start + 1..end].iter() {
+                        if let Some(common) = differ_in(group[a], group[b], &masked) {
+                            pairs.push((group[a], group[b], common));
+                        }
+                    }
+                }
+                start = end;
+            }
+            if !next_choice(&mut masked, length) {
+                break;
+            }
+        }
+    }
+    if k == 0 {
+        for (&id, _) in copies.iter() {
+            pairs.push((id, id, String::from_utf8_lossy(id).into_owned()));
+        }
+    }
+
+    let mut result = vec![];
+    for (first, second, common) in pairs {
+        for (n, &i) in copies[first].iter().enumerate() {
+            let others = if first == second {
+                &copies[second][n + 1..]
+            } else {
+                &copies[second][..]
+            };
+            for &j in others {
+                result.push(Pair {
+                    first: i.min(j),
+                    second: i.max(j),
+                    common: common.clone(),
+                });
+            }
+        }
+    }
+    result.sort_by_key(|pair| (pair.first, pair.second));
+    result
+}
+
+/// The letters of `a` and `b` outside `positions`, if they differ in exactly
+/// those positions
+fn differ_in(a: &[u8], b: &[u8], positions: &[usize]) -> Option<String> {
+    let mut common = Vec::with_capacity(a.len());
+    let mut masked = positions.iter().peekable();
+    for (position, (&x, &y)) in a.iter().zip(b.iter()).enumerate() {
+        if masked.peek() == Some(&&position) {
+            masked.next();
+            if x == y {
+                return None;
+            }
+        } else if x != y {
+            return None;
+        } else {
+            common.push(x);
+        }
+    }
+    Some(String::from_utf8_lossy(&common).into_owned())
+}
+
+/// Moves `choice`, increasing positions below `length`, to the next choice
+/// of as many positions in lexicographic order, or tells that it was the
+/// last one
+fn next_choice(choice: &mut [usize], length: usize) -> bool {
+    let k = choice.len();
+    for i in (0..k).rev() {
+        if choice[i] < length - k + i {
+            choice[i] += 1;
+            for j in i + 1..k {
+                choice[j] = choice[j - 1] + 1;
+            }
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pairs_at_distance, Pair};
+
+    #[test]
+    fn test_pairs_at_distance() {
+        let ids: Vec<String> = "abcde fghij klmno pqrst fguij axcye wvxyz abc"
+            .split(' ')
+            .map(str::to_owned)
+            .collect();
+        let pair = Pair {
+            first: 1,
+            second: 4,
+            common: "fgij".to_owned(),
+        };
+        assert_eq!(vec![pair], pairs_at_distance(&ids, 1));
+        let pair = Pair {
+            first: 0,
+            second: 5,
+            common: "ace".to_owned(),
+        };
+        assert_eq!(vec![pair], pairs_at_distance(&ids, 2));
+        assert_eq!(18, pairs_at_distance(&ids, 5).len());
+
+        // copies of an ID are at distance 0 from each other
+        let ids: Vec<String> = "abc abd abc abc".split(' ').map(str::to_owned).collect();
+        let at_1: Vec<_> = pairs_at_distance(&ids, 1)
+            .into_iter()
+            .map(|pair| (pair.first, pair.second, pair.common))
+            .collect();
+        assert_eq!(
+            vec![
+                (0, 1, "ab".to_owned()),
+                (1, 2, "ab".to_owned()),
+                (1, 3, "ab".to_owned())
+            ],
+            at_1
+        );
+        let at_0: Vec<_> = pairs_at_distance(&ids, 0)
+            .into_iter()
+            .map(|pair| (pair.first, pair.second))
+            .collect();
+        assert_eq!(vec![(0, 2), (0, 3), (2, 3)], at_0);
+    }
+}
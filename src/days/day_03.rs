@@ -1,14 +1,24 @@
-extern crate regex;
-
+use errors::*;
+use parse;
 use regex::Regex;
-use std::io::Read;
-
-fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    let grid = parse_grid(&data).unwrap();
-    println!("part_1: {}", part_1(&grid));
-    println!("part_2: {}", part_2(&grid));
+use solution::Solution;
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = ClaimGrid;
+
+    fn parse(data: &str) -> Result<ClaimGrid> {
+        parse_grid(data)
+    }
+
+    fn part_1(grid: &ClaimGrid) -> Result<String> {
+        Ok(part_1(grid).to_string())
+    }
+
+    fn part_2(grid: &ClaimGrid) -> Result<String> {
+        Ok(part_2(grid).to_string())
+    }
 }
 
 struct Claim {
@@ -19,26 +29,26 @@ struct Claim {
     h: usize,
 }
 
-struct ClaimGrid {
+pub struct ClaimGrid {
     claims: Vec<Claim>,
     rows: usize,
     cols: usize,
     data: Vec<Vec<i64>>,
 }
 
-fn parse_claim(line: &str) -> Option<Claim> {
+fn parse_claim(data: &str, line: &str) -> Result<Claim> {
     let re = Regex::new(r"^#(?P<id>\d+) @ (?P<x>\d+),(?P<y>\d+): (?P<w>\d+)x(?P<h>\d+)$").unwrap();
-    let captures = re.captures(line)?;
-    Some(Claim {
-        id: captures.name("id")?.as_str().parse().ok()?,
-        x: captures.name("x")?.as_str().parse().ok()?,
-        y: captures.name("y")?.as_str().parse().ok()?,
-        w: captures.name("w")?.as_str().parse().ok()?,
-        h: captures.name("h")?.as_str().parse().ok()?,
+    let captures = re.captures(line).chain_err(|| "invalid claim format")?;
+    Ok(Claim {
+        id: parse::value(data, &captures["id"])?,
+        x: parse::value(data, &captures["x"])?,
+        y: parse::value(data, &captures["y"])?,
+        w: parse::value(data, &captures["w"])?,
+        h: parse::value(data, &captures["h"])?,
     })
 }
 
-fn parse_grid(data: &str) -> Option<ClaimGrid> {
+fn parse_grid(data: &str) -> Result<ClaimGrid> {
     let mut grid = ClaimGrid {
         claims: Vec::new(),
         rows: 0,
@@ -47,8 +57,7 @@ fn parse_grid(data: &str) -> Option<ClaimGrid> {
     };
 
     // parse lines
-    for line in data.lines() {
-        let claim = parse_claim(line)?;
+    for claim in parse::lines(data, |line| parse_claim(data, line))? {
         grid.rows = grid.rows.max(claim.y + claim.h + 1);
         grid.cols = grid.rows.max(claim.x + claim.w + 1);
         grid.claims.push(claim);
@@ -73,7 +82,7 @@ fn parse_grid(data: &str) -> Option<ClaimGrid> {
             }
         }
     }
-    return Some(grid);
+    return Ok(grid);
 }
 
 fn part_1(grid: &ClaimGrid) -> usize {
@@ -113,8 +122,8 @@ fn part_2(grid: &ClaimGrid) -> i64 {
 mod tests {
     #[test]
     fn test_part_1() {
-        use parse_grid;
-        use part_1;
+        use super::parse_grid;
+        use super::part_1;
         let data = r"#1 @ 1,3: 4x4
 #2 @ 3,1: 4x4
 #3 @ 5,5: 2x2";
@@ -124,8 +133,8 @@ mod tests {
 
     #[test]
     fn test_part_2() {
-        use parse_grid;
-        use part_2;
+        use super::parse_grid;
+        use super::part_2;
         let data = r"#1 @ 1,3: 4x4
 #2 @ 3,1: 4x4
 #3 @ 5,5: 2x2";
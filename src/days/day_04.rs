@@ -1,66 +1,61 @@
-#![recursion_limit = "1024"]
-
-extern crate chrono;
-#[macro_use]
-extern crate error_chain;
-extern crate regex;
-
 use chrono::NaiveDateTime;
 use chrono::Timelike;
+use errors::*;
+use parse;
 use regex::Regex;
+use solution::Solution;
 use std::collections::HashMap;
-use std::io::Read;
+use Counter;
 
-mod errors {
-    error_chain!{}
-}
+pub struct Day04;
 
-use errors::*;
+impl Solution for Day04 {
+    type Input = Vec<GuardShiftEvent>;
 
-fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    let events = parse_guard_shifts(&data).unwrap();
+    fn parse(data: &str) -> Result<Vec<GuardShiftEvent>> {
+        parse_guard_shifts(data)
+    }
 
-    println!("part_1: {}", part_1(&events));
-    println!("part_2: {}", part_2(&events));
+    fn part_1(events: &Vec<GuardShiftEvent>) -> Result<String> {
+        Ok(part_1(events).to_string())
+    }
+
+    fn part_2(events: &Vec<GuardShiftEvent>) -> Result<String> {
+        Ok(part_2(events).to_string())
+    }
 }
 
 fn part_1(events: &Vec<GuardShiftEvent>) -> usize {
     let guard_minutes = collect_guard_minutes(events);
 
-    let (sleepiest_guard_id, _sleep_total) = guard_minutes
-        .iter()
-        .map(|(guard_id, counts)| (guard_id, counts.iter().sum::<usize>()))
-        .max_by_key(|(_guard_id, sum)| sum.clone())
-        .unwrap();
+    let mut sleep_totals = Counter::new();
+    for (&guard_id, minutes) in guard_minutes.iter() {
+        sleep_totals.add_n(guard_id, minutes.total());
+    }
+    let (sleepiest_guard_id, _sleep_total) = sleep_totals.argmax().unwrap();
 
     let sleepiest_guard_minutes = guard_minutes.get(sleepiest_guard_id).unwrap();
-    let sleepiest_minute = (0..sleepiest_guard_minutes.len())
-        .max_by_key(|&k| sleepiest_guard_minutes[k])
-        .unwrap();
+    let (sleepiest_minute, _) = sleepiest_guard_minutes.argmax().unwrap();
     return sleepiest_guard_id * sleepiest_minute;
 }
 
 fn part_2(events: &Vec<GuardShiftEvent>) -> usize {
     let guard_minutes = collect_guard_minutes(events);
 
-    let (guard_id, _, minute) = guard_minutes
-        .iter()
-        .map(|(guard_id, counts)| {
-            (
-                guard_id,
-                counts,
-                (0..60_usize).max_by_key(|&i| counts[i]).unwrap(),
-            )
-        }).max_by_key(|(_guard_id, counts, sleepiest_minute)| counts[*sleepiest_minute])
-        .unwrap();
+    let mut guard_minute_counts = Counter::new();
+    for (&guard_id, minutes) in guard_minutes.iter() {
+        for (&minute, count) in minutes.iter() {
+            guard_minute_counts.add_n((guard_id, minute), count);
+        }
+    }
+    let (&(guard_id, minute), _) = guard_minute_counts.argmax().unwrap();
 
     return guard_id * minute;
 }
 
-fn collect_guard_minutes(events: &Vec<GuardShiftEvent>) -> HashMap<usize, Vec<usize>> {
-    use GuardShiftEventType::*;
+/// How often every guard was asleep at every minute past midnight
+fn collect_guard_minutes(events: &Vec<GuardShiftEvent>) -> HashMap<usize, Counter<usize>> {
+    use self::GuardShiftEventType::*;
     let mut sleep_start_option: Option<NaiveDateTime> = None;
     let mut guard_minutes = HashMap::new();
     let mut guard_id = 0;
@@ -71,12 +66,10 @@ fn collect_guard_minutes(events: &Vec<GuardShiftEvent>) -> HashMap<usize, Vec<us
             }
             WakeUp | BeginShift(_) => {
                 if let Some(sleep_start) = sleep_start_option {
-                    let mut minutes = guard_minutes
-                        .entry(guard_id)
-                        .or_insert(vec![0; 60]);
+                    let minutes = guard_minutes.entry(guard_id).or_insert_with(Counter::new);
                     let mut minute_iter = sleep_start;
                     while minute_iter < event.timestamp {
-                        minutes[minute_iter.time().minute() as usize] += 1;
+                        minutes.add(minute_iter.time().minute() as usize);
                         minute_iter += chrono::Duration::minutes(1);
                     }
                 }
@@ -99,33 +92,29 @@ enum GuardShiftEventType {
 }
 
 #[derive(Debug)]
-struct GuardShiftEvent {
+pub struct GuardShiftEvent {
     timestamp: NaiveDateTime,
     event_type: GuardShiftEventType,
 }
 
 fn parse_guard_shifts(data: &str) -> Result<Vec<GuardShiftEvent>> {
     let re = Regex::new(r"^\[(?P<ts>\d{4}-\d{2}-\d{2} \d{2}:\d{2})\] (?P<event>(Guard #(?P<id>\d+))|(falls)|(wakes)).*$").unwrap();
-    let mut events = Vec::new();
-    for line in data.lines() {
+    let mut events = parse::lines(data, |line| {
         let caps = re
             .captures(line)
             .chain_err(|| "Unable to match against regular expression")?;
         let timestamp_str = caps.name("ts").chain_err(|| "No timestamp")?.as_str();
         let timestamp = NaiveDateTime::parse_from_str(timestamp_str, "%Y-%m-%d %H:%M")
-            .or(Err("Invalid timestamp"))?;
+            .chain_err(|| "Invalid timestamp")
+            .chain_err(|| parse::at(data, timestamp_str))?;
         let event_str = caps
             .name("event")
             .chain_err(|| "Invalid event format")?
             .as_str();
         let event_type: GuardShiftEventType;
         if event_str.starts_with("Guard") {
-            let guard_id = caps
-                .name("id")
-                .chain_err(|| "id not present")?
-                .as_str()
-                .parse()
-                .chain_err(|| "Unable to parse guard_id")?;
+            let guard_id = caps.name("id").chain_err(|| "id not present")?.as_str();
+            let guard_id = parse::value(data, guard_id).chain_err(|| "Unable to parse guard_id")?;
             event_type = GuardShiftEventType::BeginShift(guard_id);
         } else if event_str.starts_with("falls") {
             event_type = GuardShiftEventType::FallAsleep;
@@ -134,11 +123,11 @@ fn parse_guard_shifts(data: &str) -> Result<Vec<GuardShiftEvent>> {
         } else {
             bail!("This is not supposed to be possible");
         }
-        events.push(GuardShiftEvent {
+        Ok(GuardShiftEvent {
             timestamp: timestamp,
             event_type: event_type,
-        });
-    }
+        })
+    })?;
     events.sort_unstable_by_key(|a| a.timestamp);
     return Ok(events);
 }
@@ -147,8 +136,8 @@ fn parse_guard_shifts(data: &str) -> Result<Vec<GuardShiftEvent>> {
 mod tests {
     #[test]
     fn test_part_1() {
-        use parse_guard_shifts;
-        use part_1;
+        use super::parse_guard_shifts;
+        use super::part_1;
         let data = r"[1518-11-01 00:00] Guard #10 begins shift
 [1518-11-01 00:05] falls asleep
 [1518-11-01 00:25] wakes up
@@ -172,8 +161,8 @@ mod tests {
 
     #[test]
     fn test_part_2() {
-        use parse_guard_shifts;
-        use part_2;
+        use super::parse_guard_shifts;
+        use super::part_2;
         let data = r"[1518-11-01 00:00] Guard #10 begins shift
 [1518-11-01 00:05] falls asleep
 [1518-11-01 00:25] wakes up
@@ -1,14 +1,24 @@
-extern crate regex;
-
+use errors::*;
 use regex::Regex;
+use solution::Solution;
 use std::collections::HashSet;
-use std::io::Read;
 
-fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    println!("part_1: {}", part_1(&data));
-    println!("part_2: {}", part_2(&data));
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = String;
+
+    fn parse(data: &str) -> Result<String> {
+        Ok(data.to_owned())
+    }
+
+    fn part_1(polymer: &String) -> Result<String> {
+        Ok(part_1(polymer).to_string())
+    }
+
+    fn part_2(polymer: &String) -> Result<String> {
+        Ok(part_2(polymer).to_string())
+    }
 }
 
 fn part_1(data: &str) -> usize {
@@ -46,14 +56,14 @@ fn is_reactive(a: char, b: char) -> bool {
 mod tests {
     #[test]
     fn test_part_1() {
-        use part_1;
+        use super::part_1;
         let data = "dabAcCaCBAcCcaDA";
         assert_eq!(part_1(&data), 10);
     }
 
     #[test]
     fn test_part_2() {
-        use part_2;
+        use super::part_2;
         let data = "dabAcCaCBAcCcaDA";
         assert_eq!(part_2(&data), 4);
     }
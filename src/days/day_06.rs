@@ -1,47 +1,40 @@
-extern crate aoc2018;
+use errors::*;
+use parse;
+use solution::Solution;
+use std::collections::HashMap;
+use Counter;
+use Mat;
 
-#[macro_use]
-extern crate error_chain;
+pub struct Day06;
 
-use aoc2018::count_by_value;
-use aoc2018::Mat;
-use std::collections::HashMap;
-use std::io::Read;
+impl Solution for Day06 {
+    type Input = Vec<(usize, usize)>;
 
-mod errors {
-    error_chain!{}
-}
+    fn parse(data: &str) -> Result<Vec<(usize, usize)>> {
+        parse_coordinates(data)
+    }
 
-use errors::*;
+    fn part_1(coordinates: &Vec<(usize, usize)>) -> Result<String> {
+        Ok(part_1(coordinates).to_string())
+    }
 
-pub fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    let coordinates = parse_coordinates(&data).unwrap();
-    println!("part_1: {}", part_1(&coordinates));
-    println!("part_2: {}", part_2(&coordinates, 10_000));
+    fn part_2(coordinates: &Vec<(usize, usize)>) -> Result<String> {
+        Ok(part_2(coordinates, 10_000).to_string())
+    }
 }
 
 fn parse_coordinates(data: &str) -> Result<Vec<(usize, usize)>> {
-    let mut result = Vec::new();
-    for line in data.lines() {
+    parse::lines(data, |line| {
         let mut iter = line.split(", ");
-        let x: usize = iter
-            .next()
-            .chain_err(|| "No x coordinate")?
-            .parse()
-            .chain_err(|| "Unable to parse x coordinate")?;
-        let y: usize = iter
-            .next()
-            .chain_err(|| "No y coordinate")?
-            .parse()
-            .chain_err(|| "Unable to parse y coordinate")?;
-        result.push((x, y));
-    }
-    return Ok(result);
+        let x = iter.next().chain_err(|| "No x coordinate")?;
+        let x: usize = parse::value(data, x).chain_err(|| "Unable to parse x coordinate")?;
+        let y = iter.next().chain_err(|| "No y coordinate")?;
+        let y: usize = parse::value(data, y).chain_err(|| "Unable to parse y coordinate")?;
+        Ok((x, y))
+    })
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 enum NearestNeighborFlag {
     Closest(usize, usize),
     Neutral(usize),
@@ -59,7 +52,7 @@ fn extract_dimensions(coordinates: &Vec<(usize, usize)>) -> ((usize, usize), (us
 }
 
 fn part_1(coordinates: &Vec<(usize, usize)>) -> usize {
-    use NearestNeighborFlag::*;
+    use self::NearestNeighborFlag::*;
 
     let ((x_min, y_min), (rows, cols)) = extract_dimensions(coordinates);
 
@@ -109,12 +102,9 @@ fn part_1(coordinates: &Vec<(usize, usize)>) -> usize {
         Closest(a, b) => *is_finite.get(&(a, b)).unwrap_or(&true),
         _ => false,
     });
-    let area = count_by_value(finite_closest)
-        .values()
-        .max()
-        .unwrap()
-        .clone() as usize;
-    return area;
+    let areas: Counter<_> = finite_closest.collect();
+    let (_, area) = areas.argmax().unwrap();
+    return area as usize;
 }
 
 fn part_2(coordinates: &Vec<(usize, usize)>, limit: usize) -> usize {
@@ -143,8 +133,8 @@ fn manhattan((x, y): (usize, usize), (u, v): (usize, usize)) -> usize {
 mod tests {
     #[test]
     fn test_part_1() {
-        use parse_coordinates;
-        use part_1;
+        use super::parse_coordinates;
+        use super::part_1;
         let data = r"1, 1
 1, 6
 8, 3
@@ -158,8 +148,8 @@ mod tests {
 
     #[test]
     fn test_part_2() {
-        use parse_coordinates;
-        use part_2;
+        use super::parse_coordinates;
+        use super::part_2;
         let data = r"1, 1
 1, 6
 8, 3
@@ -1,26 +1,28 @@
-#[macro_use]
-extern crate error_chain;
-extern crate regex;
-
+use errors::*;
+use parse;
 use regex::Regex;
+use solution::Solution;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::io::Read;
 
-mod errors {
-    error_chain!{}
-}
+pub struct Day07;
 
-use errors::*;
+impl Solution for Day07 {
+    type Input = Vec<(char, char)>;
+
+    fn parse(data: &str) -> Result<Vec<(char, char)>> {
+        parse_steps(data)
+    }
+
+    fn part_1(steps: &Vec<(char, char)>) -> Result<String> {
+        Ok(part_1(steps))
+    }
 
-pub fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    let steps = parse_steps(&data).unwrap();
-    println!("part_1: {}", part_1(&steps));
-    println!("part_1: {}", part_2(&steps, 5, 60));
+    fn part_2(steps: &Vec<(char, char)>) -> Result<String> {
+        Ok(part_2(steps, 5, 60).to_string())
+    }
 }
 
 fn parse_line(line: &str) -> Result<(char, char)> {
@@ -41,11 +43,7 @@ fn parse_line(line: &str) -> Result<(char, char)> {
 
 /// Returns a map from a step to its dependencies
 fn parse_steps(data: &str) -> Result<Vec<(char, char)>> {
-    let mut res = Vec::new();
-    for line in data.lines() {
-        res.push(parse_line(line)?);
-    }
-    return Ok(res);
+    parse::lines(data, parse_line)
 }
 
 struct DependencyData {
@@ -206,8 +204,8 @@ fn part_2(steps: &Vec<(char, char)>, worker_count: usize, base_cost: usize) -> u
 mod tests {
     #[test]
     fn test_part_1() {
-        use parse_steps;
-        use part_1;
+        use super::parse_steps;
+        use super::part_1;
         let input = r"Step C must be finished before step A can begin.
 Step C must be finished before step F can begin.
 Step A must be finished before step B can begin.
@@ -222,8 +220,8 @@ Step F must be finished before step E can begin.";
 
     #[test]
     fn test_part_2() {
-        use parse_steps;
-        use part_2;
+        use super::parse_steps;
+        use super::part_2;
         let input = r"Step C must be finished before step A can begin.
 Step C must be finished before step F can begin.
 Step A must be finished before step B can begin.
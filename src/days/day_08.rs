@@ -1,24 +1,27 @@
-#[macro_use]
-extern crate error_chain;
+use errors::*;
+use parse;
+use solution::Solution;
 
-use std::io::Read;
+pub struct Day08;
 
-mod errors {
-    error_chain!{}
-}
+impl Solution for Day08 {
+    type Input = LicenseTree;
 
-use errors::*;
+    fn parse(data: &str) -> Result<LicenseTree> {
+        parse_input(data)
+    }
 
-fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    let tree = parse_input(&data).unwrap();
-    println!("part_1: {}", part_1(&tree));
-    println!("part_2: {}", part_2(&tree));
+    fn part_1(tree: &LicenseTree) -> Result<String> {
+        Ok(part_1(tree).to_string())
+    }
+
+    fn part_2(tree: &LicenseTree) -> Result<String> {
+        Ok(part_2(tree).to_string())
+    }
 }
 
 #[derive(Debug)]
-struct LicenseTree {
+pub struct LicenseTree {
     // node -> children
     structure: Vec<Vec<usize>>,
     // node -> metadata
@@ -71,15 +74,11 @@ fn parse_input(data: &str) -> Result<LicenseTree> {
             stack.push((node_id, child_count - 1, metadata_count));
             // parse next child
             let child_node_id = node_count;
-            let child_child_count: usize = iter
-                .next()
-                .chain_err(|| "expected child count")?
-                .parse()
+            let child_child_count = iter.next().chain_err(|| "expected child count")?;
+            let child_child_count: usize = parse::value(data, child_child_count)
                 .chain_err(|| "unable to parse child count")?;
-            let child_metadata_count: usize = iter
-                .next()
-                .chain_err(|| "expected metadata count")?
-                .parse()
+            let child_metadata_count = iter.next().chain_err(|| "expected metadata count")?;
+            let child_metadata_count: usize = parse::value(data, child_metadata_count)
                 .chain_err(|| "unable to parse metadata count")?;
             // put child on stack
             stack.push((child_node_id, child_child_count, child_metadata_count));
@@ -93,10 +92,8 @@ fn parse_input(data: &str) -> Result<LicenseTree> {
 
             // parse metadata
             for _ in 0..metadata_count {
-                let metadata_entry: usize = iter
-                    .next()
-                    .chain_err(|| "expected metadata entry")?
-                    .parse()
+                let metadata_entry = iter.next().chain_err(|| "expected metadata entry")?;
+                let metadata_entry: usize = parse::value(data, metadata_entry)
                     .chain_err(|| "unable to parse metadata entry")?;
                 tree.metadata[node_id].push(metadata_entry);
             }
@@ -118,8 +115,8 @@ fn part_2(tree: &LicenseTree) -> usize {
 mod tests {
     #[test]
     fn test_part_1() {
-        use parse_input;
-        use part_1;
+        use super::parse_input;
+        use super::part_1;
         let data = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2";
         let tree = parse_input(&data).unwrap();
         println!("{:?}", tree);
@@ -128,8 +125,8 @@ mod tests {
 
     #[test]
     fn test_part_2() {
-        use parse_input;
-        use part_2;
+        use super::parse_input;
+        use super::part_2;
         let data = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2";
         let tree = parse_input(&data).unwrap();
         println!("{:?}", tree);
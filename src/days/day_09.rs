@@ -1,25 +1,27 @@
-#[macro_use]
-extern crate error_chain;
-extern crate regex;
+use errors::*;
+use parse;
+use solution::Solution;
 
-use std::io::Read;
+pub struct Day09;
 
-mod errors {
-    error_chain!{}
-}
+impl Solution for Day09 {
+    type Input = Game;
 
-use errors::*;
+    fn parse(data: &str) -> Result<Game> {
+        parse_game(data)
+    }
 
-pub fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    let game = parse_game(&data).unwrap();
-    println!("part_1: {}", part_1(&game));
-    println!("part_1: {}", part_2(&game));
+    fn part_1(game: &Game) -> Result<String> {
+        Ok(part_1(game).to_string())
+    }
+
+    fn part_2(game: &Game) -> Result<String> {
+        Ok(part_2(game).to_string())
+    }
 }
 
 #[derive(Debug)]
-struct Game {
+pub struct Game {
     players: usize,
     last_marble: usize,
 }
@@ -29,18 +31,11 @@ fn parse_game(data: &str) -> Result<Game> {
         r"^(?P<players>\d+) players; last marble is worth (?P<marble>\d+) points$",
     ).unwrap();
     let caps = re.captures(data).chain_err(|| "no captures")?;
-    let players: usize = caps
-        .name("players")
-        .chain_err(|| "missing players")?
-        .as_str()
-        .parse()
-        .chain_err(|| "unable to parse players")?;
-    let last_marble: usize = caps
-        .name("marble")
-        .chain_err(|| "missing marble")?
-        .as_str()
-        .parse()
-        .chain_err(|| "unable to parse marble")?;
+    let players = caps.name("players").chain_err(|| "missing players")?.as_str();
+    let players: usize = parse::value(data, players).chain_err(|| "unable to parse players")?;
+    let last_marble = caps.name("marble").chain_err(|| "missing marble")?.as_str();
+    let last_marble: usize =
+        parse::value(data, last_marble).chain_err(|| "unable to parse marble")?;
     Ok(Game {
         players: players,
         last_marble: last_marble,
@@ -148,8 +143,8 @@ impl Circle {
 mod tests {
     #[test]
     fn test_part_1() {
-        use parse_game;
-        use part_1;
+        use super::parse_game;
+        use super::part_1;
         assert_eq!(
             part_1(&parse_game("9 players; last marble is worth 25 points").unwrap()),
             32
@@ -1,47 +1,66 @@
-extern crate aoc2018;
-#[macro_use]
-extern crate error_chain;
-extern crate regex;
+use errors::*;
+use parse;
+use render::{self, Rgb};
+use solution::Solution;
+use Mat;
 
-use aoc2018::Mat;
-use std::io::Read;
+pub struct Day10;
 
-mod errors {
-    error_chain!{}
-}
+impl Solution for Day10 {
+    type Input = Vec<Point>;
 
-use errors::*;
+    fn parse(data: &str) -> Result<Vec<Point>> {
+        parse_points(data)
+    }
+
+    fn part_1(points: &Vec<Point>) -> Result<String> {
+        let message = part_1(points).chain_err(|| "the points never line up")?;
+        Ok(message.to_string())
+    }
+
+    fn part_2(points: &Vec<Point>) -> Result<String> {
+        let seconds = part_2(points).chain_err(|| "the points never line up")?;
+        Ok(seconds.to_string())
+    }
 
-fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    let points = parse_points(&data).unwrap();
-    println!("part_1:\n{}", part_1(&points).unwrap());
-    println!("part_2:\n{}", part_2(&points).unwrap());
+    fn extras(points: &Vec<Point>, args: &[String]) -> Result<()> {
+        // optionally show the message in colour or save a picture of it
+        let message = part_1(points).chain_err(|| "the points never line up")?;
+        if let Some(path) = render::requested_image(args)? {
+            message.save_image(path, 8, colour)?;
+        }
+        if args.iter().any(|arg| arg == "--ansi") {
+            print!("{}", message.to_ansi_string(|c| (*c, colour(c))));
+        }
+        Ok(())
+    }
+}
+
+fn colour(&c: &char) -> Rgb {
+    match c {
+        '#' => Rgb(255, 200, 0),
+        _ => Rgb(0, 0, 64),
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
-struct Point {
+pub struct Point {
     pos: (i64, i64),
     vel: (i64, i64),
 }
 
 fn parse_points(data: &str) -> Result<Vec<Point>> {
-    let mut result = Vec::new();
-    for line in data.lines() {
-        result.push(parse_point(line)?);
-    }
-    Ok(result)
+    parse::lines(data, |line| parse_point(data, line))
 }
 
-fn parse_point(data: &str) -> Result<Point> {
+fn parse_point(data: &str, line: &str) -> Result<Point> {
     let re = regex::Regex::new(r"^position=<(?P<pos>.*)> velocity=<(?P<vel>.*)>$").unwrap();
-    let caps = re.captures(data).chain_err(|| "no match")?;
+    let caps = re.captures(line).chain_err(|| "no match")?;
     let pos_str = caps.name("pos").chain_err(|| "no pos found")?.as_str();
     let vel_str = caps.name("vel").chain_err(|| "no vel found")?.as_str();
     Ok(Point {
-        pos: parse_pair(pos_str)?,
-        vel: parse_pair(vel_str)?,
+        pos: parse_pair(data, pos_str)?,
+        vel: parse_pair(data, vel_str)?,
     })
 }
 
@@ -126,16 +145,12 @@ fn draw(points: &Vec<Point>, bb: &BoundingBox) -> Mat<char> {
     mat
 }
 
-fn parse_pair(data: &str) -> Result<(i64, i64)> {
-    let mut iter = data.split(",").map(|s| s.trim());
+fn parse_pair(data: &str, pair: &str) -> Result<(i64, i64)> {
+    let mut iter = pair.split(",");
     let first_str = iter.next().chain_err(|| "expected a coordinate")?;
-    let first: i64 = first_str
-        .parse()
-        .chain_err(|| format!("unable to parse integer {}", first_str))?;
+    let first: i64 = parse::value(data, first_str)?;
     let second_str = iter.next().chain_err(|| "expected a coordinate")?;
-    let second: i64 = second_str
-        .parse()
-        .chain_err(|| format!("unable to parse integer {}", second_str))?;
+    let second: i64 = parse::value(data, second_str)?;
     Ok((first, second))
 }
 
@@ -143,8 +158,8 @@ fn parse_pair(data: &str) -> Result<(i64, i64)> {
 mod tests {
     #[test]
     fn test_part_1() {
-        use part_1;
-        use parse_points;
+        use super::part_1;
+        use super::parse_points;
         let data = r"position=< 9,  1> velocity=< 0,  2>
 position=< 7,  0> velocity=<-1,  0>
 position=< 3, -2> velocity=<-1,  1>
@@ -182,8 +197,8 @@ position=<-3,  6> velocity=< 2, -1>";
 
     #[test]
     fn test_part_2() {
-        use part_2;
-        use parse_points;
+        use super::part_2;
+        use super::parse_points;
         let data = r"position=< 9,  1> velocity=< 0,  2>
 position=< 7,  0> velocity=<-1,  0>
 position=< 3, -2> velocity=<-1,  1>
@@ -1,15 +1,27 @@
-extern crate aoc2018;
+use errors::*;
+use parse;
+use solution::Solution;
+use Mat;
 
-use aoc2018::Mat;
-use std::io::Read;
+pub struct Day11;
 
-fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    let serial_number: usize = data.parse().unwrap();
-    let grid = init_fuel_grid(serial_number);
-    println!("part_1: {:?}", part_1(&grid));
-    println!("part_2: {:?}", part_2(&grid));
+impl Solution for Day11 {
+    type Input = Mat<i64>;
+
+    fn parse(data: &str) -> Result<Mat<i64>> {
+        let serial_number = parse::value(data, data)?;
+        Ok(init_fuel_grid(serial_number))
+    }
+
+    fn part_1(grid: &Mat<i64>) -> Result<String> {
+        let (_, (x, y)) = part_1(grid);
+        Ok(format!("{},{}", x, y))
+    }
+
+    fn part_2(grid: &Mat<i64>) -> Result<String> {
+        let (_, (x, y), size) = part_2(grid);
+        Ok(format!("{},{},{}", x, y, size))
+    }
 }
 
 fn init_fuel_grid(serial_number: usize) -> Mat<i64> {
@@ -33,12 +45,7 @@ pub fn part_1(grid: &Mat<i64>) -> (i64, (usize, usize)) {
     let mut max_power = 0;
     for y0 in 0..grid.rows() - 3 {
         for x0 in 0..grid.cols() - 3 {
-            let mut power = 0;
-            for y in y0..y0 + 3 {
-                for x in x0..x0 + 3 {
-                    power += grid.get(y, x);
-                }
-            }
+            let power: i64 = grid.window(y0, x0, 3, 3).iter().sum();
             if power > max_power {
                 max_power = power;
                 max_corner = (x0, y0);
@@ -57,12 +64,8 @@ pub fn part_2(grid: &Mat<i64>) -> (i64, (usize, usize), usize) {
         for y0 in 0..grid.rows() - dim {
             for x0 in 0..grid.cols() - dim {
                 let mut power = *cache.get(y0, x0);
-                for x in x0..x0 + dim {
-                    power += grid.get(y0 + dim - 1, x);
-                }
-                for y in y0..y0 + dim {
-                    power += grid.get(y, x0 + dim - 1);
-                }
+                power += grid.row(y0 + dim - 1)[x0..x0 + dim].iter().sum::<i64>();
+                power += grid.col(x0 + dim - 1).skip(y0).take(dim).sum::<i64>();
                 power -= grid.get(y0 + dim - 1, x0 + dim - 1);
                 if power > max_power {
                     max_power = power;
@@ -76,13 +79,12 @@ pub fn part_2(grid: &Mat<i64>) -> (i64, (usize, usize), usize) {
     (max_power, max_corner, max_dim)
 }
 
-
 #[cfg(test)]
 mod tests {
     #[test]
     fn test_part_1() {
-        use init_fuel_grid;
-        use part_1;
+        use super::init_fuel_grid;
+        use super::part_1;
 
         let grid_18 = init_fuel_grid(18);
         assert_eq!(part_1(&grid_18), (29, (33, 45)));
@@ -93,8 +95,8 @@ mod tests {
 
     #[test]
     fn test_part_2() {
-        use init_fuel_grid;
-        use part_2;
+        use super::init_fuel_grid;
+        use super::part_2;
 
         let grid_18 = init_fuel_grid(18);
         assert_eq!(part_2(&grid_18), (113, (90, 269), 16));
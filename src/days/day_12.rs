@@ -1,30 +1,48 @@
-#[macro_use]
-extern crate error_chain;
-
+use cycle;
+use errors::*;
+use parse;
+use solution::Solution;
 use std::collections::HashMap;
-use std::io::Read;
 
-pub mod errors {
-    error_chain!{}
-}
+pub struct Day12;
 
-use errors::*;
+impl Solution for Day12 {
+    type Input = (String, Vec<(String, String)>);
+
+    fn parse(data: &str) -> Result<(String, Vec<(String, String)>)> {
+        let (initial_state, rule_map) = parse_input(data)?;
+        let rules = rule_map
+            .into_iter()
+            .map(|(from, to)| (from.to_owned(), to.to_owned()))
+            .collect();
+        Ok((initial_state.to_owned(), rules))
+    }
+
+    fn part_1(input: &(String, Vec<(String, String)>)) -> Result<String> {
+        Ok(part_1(&input.0, &rule_map(&input.1)).to_string())
+    }
 
-pub fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    let (initial_state, rule_map) = parse_input(&data).unwrap();
-    println!("part_1: {}", part_1(initial_state, &rule_map));
-    println!("part_2: {}", part_2(initial_state, &rule_map));
+    fn part_2(input: &(String, Vec<(String, String)>)) -> Result<String> {
+        Ok(part_2(&input.0, &rule_map(&input.1)).to_string())
+    }
+}
+
+/// The input keeps its own copy of the rules, which the parts take borrowed
+fn rule_map(rules: &[(String, String)]) -> HashMap<&str, &str> {
+    rules
+        .iter()
+        .map(|(from, to)| (from.as_str(), to.as_str()))
+        .collect()
 }
 
 fn parse_input<'a>(data: &'a str) -> Result<(&'a str, HashMap<&'a str, &'a str>)> {
     let mut lines = data.lines();
-    let initial_state = parse_initial_state(lines.next().chain_err(|| "no initial state given")?)?;
+    let first = lines.next().chain_err(|| "no initial state given")?;
+    let initial_state = parse_initial_state(first).chain_err(|| parse::at(data, first))?;
     lines.next(); // discard
     let mut rule_map = HashMap::new();
     for line in lines {
-        let (from, to) = parse_rule(line)?;
+        let (from, to) = parse_rule(line).chain_err(|| parse::at(data, line))?;
         rule_map.insert(from, to);
     }
     Ok((initial_state, rule_map))
@@ -84,41 +102,33 @@ fn part_1(initial_state: &str, rule_map: &HashMap<&str, &str>) -> usize {
 }
 
 fn part_2(initial_state: &str, rule_map: &HashMap<&str, &str>) -> usize {
-    let mut state = initial_state.to_owned();
-    let mut prev_trimmed_state = state.trim_matches('.').to_owned();
-    let mut sum;;
-    let mut prev_sum = 0;
-    let mut round = 0;
-    loop {
-        round += 1;
-        state = apply_rules(state.as_str(), rule_map);
-        sum = state
-            .chars()
-            .enumerate()
-            .filter(|(_, c)| *c == '#')
-            .map(|(i, _)| i - round * 2)
-            .sum();
-        let trimmed_state = state.trim_matches('.').to_owned();
-        if prev_trimmed_state == trimmed_state {
-            // found cycle
-            break;
-        }
-        prev_trimmed_state = trimmed_state;
-        prev_sum = sum;
-    }
-    let diff = sum - prev_sum;
-    let target = 50_000_000_000 - round;
-    sum + target * diff
+    // the plants settle into a pattern that keeps moving at a constant speed,
+    // so states repeat up to a shift, with the sum growing by the same amount
+    let initial = (initial_state.to_owned(), 0);
+    let sum = cycle::nth_value_drifting(
+        initial,
+        |&(ref state, round)| (apply_rules(state, rule_map), round + 1),
+        50_000_000_000,
+        |&(ref state, _)| state.trim_matches('.').to_owned(),
+        |&(ref state, round)| {
+            state
+                .chars()
+                .enumerate()
+                .filter(|(_, c)| *c == '#')
+                .map(|(i, _)| i as i64 - round as i64 * 2)
+                .sum()
+        },
+    );
+    sum as usize
 }
 
-
 #[cfg(test)]
 mod tests {
     #[test]
     fn test_part_1() {
-        use apply_rules;
-        use parse_input;
-        use part_1;
+        use super::apply_rules;
+        use super::parse_input;
+        use super::part_1;
         let data = r"initial state: #..#.#..##......###...###
 
 ...## => #
@@ -1,17 +1,47 @@
-extern crate aoc2018;
-
-use aoc2018::Mat;
+use errors::*;
+use record::{self, Recorder};
+use render::{self, Rgb};
+use solution::Solution;
 use std::collections::BTreeMap;
-use std::io::Read;
+use Mat;
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = TrackState;
+
+    fn parse(data: &str) -> Result<TrackState> {
+        parse_track(data)
+    }
+
+    fn part_1(track: &TrackState) -> Result<String> {
+        let (y, x) = part_1(track);
+        Ok(format!("{},{}", x, y))
+    }
+
+    fn part_2(track: &TrackState) -> Result<String> {
+        let (y, x) = part_2(track);
+        Ok(format!("{},{}", x, y))
+    }
 
-fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    let track = parse_track(&data);
-    let (y1, x1) = part_1(&track);
-    println!("part_1: {},{}", x1, y1);
-    let (y2, x2) = part_2(&track);
-    println!("part_2: {},{}", x2, y2);
+    fn extras(track: &TrackState, args: &[String]) -> Result<()> {
+        // optionally show the track at the first crash
+        let crashed = || {
+            let mut track = track.clone();
+            while track.tick().is_none() {}
+            track.frame()
+        };
+        if let Some(path) = render::requested_image(args)? {
+            crashed().save_image(path, 2, colour)?;
+        }
+        if args.iter().any(|arg| arg == "--ansi") {
+            print!("{}", crashed().to_ansi_string(|c| (*c, colour(c))));
+        }
+        if let Some(path) = record::requested(args) {
+            animate(track, path)?;
+        }
+        Ok(())
+    }
 }
 
 fn part_1(track0: &TrackState) -> (usize, usize) {
@@ -32,8 +62,29 @@ fn part_2(track0: &TrackState) -> (usize, usize) {
     *track.carts.keys().next().unwrap()
 }
 
+/// Records the carts until only one is left
+fn animate(track0: &TrackState, path: &str) -> Result<()> {
+    // carts run for thousands of ticks, so keep one frame in ten
+    let mut recorder = Recorder::open(path, 2, colour)?.every(10);
+    let mut track = track0.clone();
+    recorder.record(&track.frame())?;
+    while track.carts.len() > 1 {
+        track.tick();
+        recorder.record(&track.frame())?;
+    }
+    recorder.finish()
+}
+
+fn colour(&c: &char) -> Rgb {
+    match c {
+        ' ' => Rgb::BLACK,
+        '^' | 'v' | '<' | '>' => Rgb(255, 64, 0),
+        _ => Rgb(128, 128, 128),
+    }
+}
+
 #[derive(Debug, Clone)]
-struct TrackState {
+pub struct TrackState {
     grid: Mat<char>,
     carts: BTreeMap<(usize, usize), (char, usize)>,
 }
@@ -55,6 +106,15 @@ impl std::fmt::Display for TrackState {
 }
 
 impl TrackState {
+    /// The track with the carts drawn on it
+    fn frame(&self) -> Mat<char> {
+        let mut frame = self.grid.clone();
+        for (&(row, col), &(direction, _)) in self.carts.iter() {
+            frame.set(row, col, direction);
+        }
+        frame
+    }
+
     fn tick(&mut self) -> Option<(usize, usize)> {
         let mut next_carts = BTreeMap::new();
         let mut collision = None;
@@ -140,21 +200,19 @@ fn turn_right(c: char) -> char {
     }
 }
 
-fn parse_track(data: &str) -> TrackState {
-    let rows = data.lines().count();
-    let cols = data.lines().next().unwrap_or(&"").chars().count();
-    let mut grid = Mat::new(rows, cols, char::default());
+fn parse_track(data: &str) -> Result<TrackState> {
+    // lines may lack trailing spaces, so pad them up to the widest
+    let mut grid = Mat::parse_padded(data, ' ', |c| match c {
+        ' ' | '-' | '|' | '/' | '\\' | '+' | '^' | 'v' | '>' | '<' => Ok(c),
+        _ => bail!("invalid track piece '{}'", c),
+    })?;
     let mut carts = BTreeMap::new();
-    // parse raw data
-    for (row, line) in data.lines().enumerate() {
-        for (col, val) in line.chars().enumerate() {
-            match val {
-                '^' | 'v' | '>' | '<' => {
-                    carts.insert((row, col), (val, 0));
-                }
-                _ => (),
-            };
-            grid.set(row, col, val);
+    for row in 0..grid.rows() {
+        for col in 0..grid.cols() {
+            let val = *grid.get(row, col);
+            if "^v><".contains(val) {
+                carts.insert((row, col), (val, 0));
+            }
         }
     }
     // lift carts off the grid
@@ -218,18 +276,18 @@ fn parse_track(data: &str) -> TrackState {
             continue;
         }
     }
-    TrackState {
+    Ok(TrackState {
         grid: grid,
         carts: carts,
-    }
+    })
 }
 
 #[cfg(test)]
 mod tests {
     #[test]
     fn test_part_1() {
-        use parse_track;
-        use part_1;
+        use super::parse_track;
+        use super::part_1;
         let simple_input = r"|
 v
 |
@@ -237,7 +295,7 @@ v
 |
 ^
 |";
-        let simple_track = parse_track(&simple_input);
+        let simple_track = parse_track(&simple_input).unwrap();
         assert_eq!((3, 0), part_1(&simple_track));
         let input = [
             r"/->-\        ",
@@ -248,9 +306,12 @@ v
             r"  \------/   ",
         ]
             .join("\n");
-        let track = parse_track(&input);
+        let track = parse_track(&input).unwrap();
         println!("{}", track.grid);
         assert_eq!((3, 7), part_1(&track));
+        // without trailing whitespace
+        let trimmed: Vec<&str> = input.lines().map(|line| line.trim_end()).collect();
+        assert_eq!((3, 7), part_1(&parse_track(&trimmed.join("\n")).unwrap()));
 
         assert_eq!(
             (0, 5),
@@ -258,7 +319,7 @@ v
                 r"/--->>---\
 ^        |
 \--------/"
-            ))
+            ).unwrap())
         );
         assert_eq!(
             (2, 0),
@@ -267,19 +328,19 @@ v
 v       |
 v       |
 \-------/"
-            ))
+            ).unwrap())
         );
     }
 
     #[test]
     fn test_part_2() {
-        use parse_track;
-        use part_2;
+        use super::parse_track;
+        use super::part_2;
         let input = [
             r"/>-<\  ", r"|   |  ", r"| /<+-\", r"| | | v", r"\>+</ |", r"  |   ^", r"  \<->/",
         ]
             .join("\n");
-        let track = parse_track(&input);
+        let track = parse_track(&input).unwrap();
         println!("{}", track.grid);
         assert_eq!((4, 6), part_2(&track));
     }
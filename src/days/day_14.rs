@@ -1,11 +1,23 @@
-use std::io::Read;
-
-fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    let recipe_count: usize = data.parse().unwrap();
-    println!("part_1: {}", part_1(recipe_count));
-    println!("part_2: {}", part_2(recipe_count.to_string().as_str()));
+use errors::*;
+use parse;
+use solution::Solution;
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = usize;
+
+    fn parse(data: &str) -> Result<usize> {
+        parse::value(data, data)
+    }
+
+    fn part_1(recipe_count: &usize) -> Result<String> {
+        Ok(part_1(*recipe_count))
+    }
+
+    fn part_2(recipe_count: &usize) -> Result<String> {
+        Ok(part_2(&recipe_count.to_string()).to_string())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -95,7 +107,7 @@ fn part_2(pattern_str: &str) -> usize {
 mod tests {
     #[test]
     fn test_part_1() {
-        use part_1;
+        use super::part_1;
         assert_eq!("5158916779", part_1(9));
         assert_eq!("0124515891", part_1(5));
         assert_eq!("9251071085", part_1(18));
@@ -104,7 +116,7 @@ mod tests {
 
     #[test]
     fn test_part_2() {
-        use part_2;
+        use super::part_2;
         assert_eq!(9, part_2("51589"));
         assert_eq!(5, part_2("01245"));
         assert_eq!(18, part_2("92510"));
@@ -1,17 +1,55 @@
-extern crate aoc2018;
-
-use aoc2018::Mat;
+use errors::*;
+use record::{self, Recorder};
+use render::Rgb;
+use search;
+use solution::Solution;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
-use std::io::Read;
-
-pub fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    let state = parse_state(&data).unwrap();
-    println!("part_1: {:?}", part_1(&state));
-    println!("part_2: {:?}", part_2(&state));
+use Mat;
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = State;
+
+    fn parse(data: &str) -> Result<State> {
+        parse_state(data)
+    }
+
+    fn part_1(state: &State) -> Result<String> {
+        let (_, _, outcome) = part_1(state);
+        Ok(outcome.to_string())
+    }
+
+    fn part_2(state: &State) -> Result<String> {
+        Ok(part_2(state).to_string())
+    }
+
+    fn extras(state: &State, args: &[String]) -> Result<()> {
+        // optionally show how the first battle ended
+        if args.iter().any(|arg| arg == "--ansi") {
+            let mut battle = state.clone();
+            battle.resolve_battle();
+            print!("{}", battle.map.to_ansi_string(|cell| (cell.symbol(), cell.colour())));
+        }
+        if let Some(path) = record::requested(args) {
+            animate(state, path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Records the first battle, one frame per round
+fn animate(state_0: &State, path: &str) -> Result<()> {
+    let mut recorder = Recorder::open(path, 8, Cell::colour)?;
+    let mut state = state_0.clone();
+    recorder.record(&state.map)?;
+    while state.make_turn() {
+        recorder.record(&state.map)?;
+    }
+    recorder.record(&state.map)?;
+    recorder.finish()
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -24,7 +62,7 @@ enum Cell {
 
 impl Cell {
     fn is_enemy(&self, other: Cell) -> bool {
-        use Cell::*;
+        use self::Cell::*;
         match self {
             Elf(_) => match other {
                 Goblin(_) => true,
@@ -39,7 +77,7 @@ impl Cell {
     }
 
     fn is_world(&self) -> bool {
-        use Cell::*;
+        use self::Cell::*;
         match self {
             Open | Wall => true,
             _ => false,
@@ -65,7 +103,7 @@ impl Cell {
     }
 
     fn hitpoints(&self) -> usize {
-        use Cell::*;
+        use self::Cell::*;
         match *self {
             Open | Wall => usize::max_value(),
             Elf(hp) | Goblin(hp) => hp,
@@ -73,7 +111,7 @@ impl Cell {
     }
 
     fn attacked(&self, damage: usize) -> Cell {
-        use Cell::*;
+        use self::Cell::*;
         match *self {
             Elf(hp) => {
                 if hp <= damage {
@@ -92,45 +130,56 @@ impl Cell {
             _ => *self,
         }
     }
+
+    fn symbol(&self) -> char {
+        use self::Cell::*;
+        match self {
+            Wall => '#',
+            Open => '.',
+            Elf(_) => 'E',
+            Goblin(_) => 'G',
+        }
+    }
+
+    /// Units fade from their full colour as they lose hit points
+    fn colour(&self) -> Rgb {
+        use self::Cell::*;
+        let faded = Rgb(64, 64, 64);
+        match *self {
+            Wall => Rgb(160, 160, 160),
+            Open => faded,
+            Elf(hp) => faded.blend(Rgb(0, 255, 0), hp as f64 / 200.0),
+            Goblin(hp) => faded.blend(Rgb(255, 0, 0), hp as f64 / 200.0),
+        }
+    }
 }
 
-fn parse_cell(c: char) -> Result<Cell, &'static str> {
-    use Cell::*;
+fn parse_cell(c: char) -> Result<Cell> {
+    use self::Cell::*;
     match c {
         '#' => Ok(Wall),
         '.' => Ok(Open),
         'E' => Ok(Elf(200)),
         'G' => Ok(Goblin(200)),
-        _ => Err("Invalid cell"),
+        _ => bail!("Invalid cell '{}'", c),
     }
 }
 
 impl fmt::Display for Cell {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        use Cell::*;
-        let c = match self {
-            Wall => '#',
-            Open => '.',
-            Elf(_) => 'E',
-            Goblin(_) => 'G',
-        };
-        write!(f, "{}", c)?;
-        Ok(())
+        write!(f, "{}", self.symbol())
     }
 }
 
-fn parse_state(data: &str) -> Result<State, &'static str> {
-    let rows = data.lines().count();
-    let cols = data.lines().next().unwrap_or("").chars().count();
-    let mut map = Mat::new(rows, cols, Cell::Open);
+fn parse_state(data: &str) -> Result<State> {
+    let map = Mat::parse_with(data, parse_cell)?;
     let mut units = HashMap::new();
-    for (row, line) in data.lines().enumerate() {
-        for (col, c) in line.chars().enumerate() {
-            let cell = parse_cell(c)?;
+    for row in 0..map.rows() {
+        for col in 0..map.cols() {
+            let cell = *map.get(row, col);
             if cell.is_unit() {
                 units.insert((row, col), cell);
             }
-            map.set(row, col, cell);
         }
     }
     Ok(State {
@@ -142,7 +191,7 @@ fn parse_state(data: &str) -> Result<State, &'static str> {
 }
 
 #[derive(Debug, Clone)]
-struct State {
+pub struct State {
     map: Mat<Cell>,
     units: HashMap<(usize, usize), Cell>,
     round: usize,
@@ -217,46 +266,25 @@ impl State {
                 }
             }
         }
-        let mut seen = HashSet::new();
-        seen.insert(source);
-        let mut queue = std::collections::VecDeque::new();
-        queue.push_back((0, source, None));
-        let mut maybe_min_target = None;
-        while !queue.is_empty() {
-            let (d, p, q) = queue.pop_front().unwrap();
-            if let Some((d0, _, _)) = maybe_min_target {
-                if d0 < d {
-                    break;
-                }
-            }
-            if targets.contains(&p) {
-                maybe_min_target = maybe_min_target
-                    .or(Some((d, p, q)))
-                    .map(|x| x.min((d, p, q)));
-                // no point in looking at the neighbors
-                continue;
-            }
-            for &n in self.neighbors(p).iter() {
-                if seen.contains(&n) {
-                    continue;
-                }
-                if *self.map.get(n.0, n.1) == Cell::Open {
-                    seen.insert(n);
-                    queue.push_back((d + 1, n, if d == 1 { Some(p) } else { q }));
-                }
-            }
+        let open = |p: &(usize, usize)| -> Vec<(usize, usize)> {
+            self.neighbors(*p)
+                .into_iter()
+                .filter(|n| *self.map.get(n.0, n.1) == Cell::Open)
+                .collect()
+        };
+        let target = search::bfs(source, |p| open(p), |p| targets.contains(p)).goal?;
+        if target == source {
+            return Some(source);
         }
-        maybe_min_target.map(|(_, p, q)| q.unwrap_or(p))
+        // of the first steps on a shortest path to the target, take the first
+        // in reading order
+        let first_steps = open(&source);
+        search::bfs(target, |p| open(p), |p| first_steps.contains(p)).goal
     }
 
     /// returns the lexicographically sorted neighbors
     fn neighbors(&self, (row, col): (usize, usize)) -> Vec<(usize, usize)> {
-        vec![
-            (row - 1, col),
-            (row, col - 1),
-            (row, col + 1),
-            (row + 1, col),
-        ]
+        self.map.neighbors4(row, col).collect()
     }
 
     fn resolve_battle(&mut self) {
@@ -307,7 +335,7 @@ fn part_2(state_0: &State) -> usize {
 mod tests {
     #[test]
     fn test_find_move() {
-        use parse_state;
+        use super::parse_state;
         let mut data = r"#######
 #E..G.#
 #...#.#
@@ -327,7 +355,7 @@ mod tests {
 
     #[test]
     fn test_make_turn() {
-        use parse_state;
+        use super::parse_state;
         let mut state = parse_state(
             &r"#########
 #G..G..G#
@@ -357,8 +385,8 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        use parse_state;
-        use part_1;
+        use super::parse_state;
+        use super::part_1;
         let mut data_map = Vec::new();
         data_map.push((
             r"#######
@@ -0,0 +1,147 @@
+use elfcode;
+use errors::*;
+use parse;
+use solution::Solution;
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = (Vec<Sample>, Vec<Instruction>);
+
+    fn parse(data: &str) -> Result<(Vec<Sample>, Vec<Instruction>)> {
+        parse_input(data)
+    }
+
+    fn part_1(input: &(Vec<Sample>, Vec<Instruction>)) -> Result<String> {
+        Ok(part_1(&input.0).to_string())
+    }
+
+    fn part_2(input: &(Vec<Sample>, Vec<Instruction>)) -> Result<String> {
+        Ok(part_2(&input.0, &input.1)?.to_string())
+    }
+}
+
+fn part_1(samples: &Vec<Sample>) -> usize {
+    samples
+        .iter()
+        .map(|sample| sample.candidates().len())
+        .filter(|&c| c >= 3)
+        .count()
+}
+
+fn part_2(samples: &Vec<Sample>, program: &Vec<Instruction>) -> Result<i64> {
+    let resolved = elfcode::infer_opcodes(samples)?;
+    let mut registers = [0, 0, 0, 0];
+    for (line, &(opcode, args)) in program.iter().enumerate() {
+        let op = resolved.get(&opcode).chain_err(|| {
+            format!(
+                "opcode {} on line {} never occurs in the samples",
+                opcode, line
+            )
+        })?;
+        registers = op
+            .execute(args, registers, elfcode::Arithmetic::Checked)
+            .map_err(|fault| {
+                let text = format!("{} {} {} {}", op.name(), args.0, args.1, args.2);
+                elfcode::errors::Error::from(elfcode::errors::ErrorKind::Fault(line, text, fault))
+            })?;
+    }
+    Ok(registers[0])
+}
+
+type Args = elfcode::Args<i64>;
+type Instruction = (usize, Args);
+type Sample = elfcode::Sample<i64, 4>;
+
+fn parse_input(data: &str) -> Result<(Vec<Sample>, Vec<Instruction>)> {
+    let mut samples = Vec::new();
+    for sample_str in data.split("\n\n") {
+        if !sample_str.starts_with("Before") {
+            break;
+        }
+        samples.push(parse_sample(data, sample_str).chain_err(|| "unable to parse sample")?);
+    }
+    let sample_lines = samples.len() * 4 + 2;
+    let mut test_program = Vec::new();
+    for line in data.lines().skip(sample_lines) {
+        let opargs = parse_four(data, line, " ").chain_err(|| "unable to parse instruction")?;
+        test_program.push((opargs[0] as usize, (opargs[1], opargs[2], opargs[3])));
+    }
+    Ok((samples, test_program))
+}
+
+/// Parses `sample`, a slice of `data`
+fn parse_sample(data: &str, sample: &str) -> Result<Sample> {
+    let re = regex::Regex::new(
+        r"(?m)Before: \[(?P<before>.*)\]
+(?P<opargs>.*)
+After:  \[(?P<after>.*)\]",
+    ).unwrap();
+    let caps = re.captures(sample).chain_err(|| parse::at(data, sample))?;
+    let opargs = parse_four(data, &caps["opargs"], " ")?;
+    Ok(Sample {
+        before: parse_four(data, &caps["before"], ", ")?,
+        opcode: opargs[0] as usize,
+        args: (opargs[1], opargs[2], opargs[3]),
+        after: parse_four(data, &caps["after"], ", ")?,
+    })
+}
+
+/// Parses four numbers separated by `separator` from `text`, a slice of `data`
+fn parse_four(data: &str, text: &str, separator: &str) -> Result<[i64; 4]> {
+    let values = text
+        .split(separator)
+        .map(|s| parse::value(data, s))
+        .collect::<Result<Vec<i64>>>()?;
+    if values.len() != 4 {
+        return Err(Error::from(format!("expected 4 numbers, got {}", values.len())))
+            .chain_err(|| parse::at(data, text));
+    }
+    Ok([values[0], values[1], values[2], values[3]])
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_parse_sample() {
+        use super::parse_sample;
+        use super::Sample;
+        let sample_str = r"Before: [3, 2, 1, 1]
+9 2 1 2
+After:  [3, 2, 2, 1]";
+        let sample = parse_sample(&sample_str, &sample_str).unwrap();
+        assert_eq!(
+            sample,
+            Sample {
+                before: [3, 2, 1, 1],
+                opcode: 9,
+                args: (2, 1, 2),
+                after: [3, 2, 2, 1],
+            }
+        );
+    }
+
+    #[test]
+    fn test_ops() {
+        use super::parse_sample;
+        let sample_str = r"Before: [3, 2, 1, 1]
+9 2 1 2
+After:  [3, 2, 2, 1]";
+        let sample = parse_sample(&sample_str, &sample_str).unwrap();
+        let matches: Vec<_> = sample.candidates().iter().map(|op| op.name()).collect();
+        println!("{:?}", matches);
+        assert_eq!(matches.len(), 3);
+    }
+
+    #[test]
+    fn test_unknown_opcode() {
+        use super::{parse_sample, part_2};
+        // only seti sets r[0] to 7 here
+        let sample_str = r"Before: [0, 0, 0, 0]
+5 7 0 0
+After:  [7, 0, 0, 0]";
+        let samples = vec![parse_sample(&sample_str, &sample_str).unwrap()];
+        assert_eq!(7, part_2(&samples, &vec![(5, (7, 0, 0))]).unwrap());
+        assert!(part_2(&samples, &vec![(3, (7, 0, 0))]).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use errors::*;
+use grid::Offset;
+use parse;
+use regex::Regex;
+use render::{self, Rgb};
+use solution::Solution;
+use std::fmt;
+use Grid;
+use Mat;
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = (Ground, Offset);
+
+    fn parse(data: &str) -> Result<(Ground, Offset)> {
+        parse_ground(data)
+    }
+
+    fn part_1(input: &(Ground, Offset)) -> Result<String> {
+        Ok(part_1(&input.0, input.1).to_string())
+    }
+
+    fn part_2(input: &(Ground, Offset)) -> Result<String> {
+        Ok(part_2(&input.0, input.1).to_string())
+    }
+
+    fn extras(&(ref ground, origin): &(Ground, Offset), args: &[String]) -> Result<()> {
+        // optionally save a picture of where the water went
+        if let Some(path) = render::requested_image(args)? {
+            flood(ground, origin).save_image(path, 1, Square::colour)?;
+        }
+        Ok(())
+    }
+}
+
+fn part_1(ground_0: &Ground, origin: Offset) -> usize {
+    flood(ground_0, origin)
+        .iter()
+        .filter(|&&square| square == Square::Water || square == Square::Flow)
+        .count()
+}
+
+fn part_2(ground_0: &Ground, origin: Offset) -> usize {
+    flood(ground_0, origin)
+        .iter()
+        .filter(|&&square| square == Square::Water)
+        .count()
+}
+
+/// The ground after water from the spring has settled
+fn flood(ground_0: &Ground, origin: Offset) -> Ground {
+    let mut ground = ground_0.clone();
+    drip(
+        &mut ground,
+        0,
+        (500 - origin.col) as usize,
+        DripDirection::Down,
+    );
+    ground
+}
+
+fn drip(ground: &mut Ground, row: usize, col: usize, dir: DripDirection) -> bool {
+    use self::DripDirection::*;
+    use self::Square::*;
+    if row == ground.rows() || col == ground.cols() {
+        return true;
+    }
+    match *ground.get(row, col) {
+        Water | Clay => return false,
+        Sand | Flow => (),
+    };
+    if col == 0 {
+        return true;
+    }
+    ground.set(row, col, Flow);
+    if drip(ground, row + 1, col, Down) {
+        return true;
+    }
+    let left = dir != Right && drip(ground, row, col - 1, Left);
+    let right = dir != Left && drip(ground, row, col + 1, Right);
+    if !(left || right) {
+        ground.set(row, col, Water);
+        return false;
+    }
+    if !left && right {
+        let mut icol = col - 1;
+        while *ground.get(row, icol) == Water {
+            ground.set(row, icol, Flow);
+            if icol == 0 {
+                break;
+            }
+            icol -= 1;
+        }
+    }
+    if left && !right {
+        let mut icol = col + 1;
+        while icol < ground.cols() && *ground.get(row, icol) == Water {
+            ground.set(row, icol, Flow);
+            icol += 1;
+        }
+    }
+    true
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum DripDirection {
+    Left,
+    Down,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Square {
+    Sand,
+    Clay,
+    Flow,
+    Water,
+}
+
+impl Square {
+    fn colour(&self) -> Rgb {
+        match *self {
+            Square::Sand => Rgb(237, 201, 175),
+            Square::Clay => Rgb(120, 72, 0),
+            Square::Flow => Rgb(150, 200, 255),
+            Square::Water => Rgb(0, 80, 255),
+        }
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::Square::*;
+        let c = match self {
+            Sand => '.',
+            Clay => '#',
+            Flow => '|',
+            Water => '~',
+        };
+        write!(f, "{}", c)?;
+        Ok(())
+    }
+}
+
+type Ground = Mat<Square>;
+
+fn parse_ground(data: &str) -> Result<(Ground, Offset)> {
+    let x_re = Regex::new(r"x=(\d+)(\.\.(\d+))?").unwrap();
+    let y_re = Regex::new(r"y=(\d+)(\.\.(\d+))?").unwrap();
+    let parse_range = |re: &Regex, line: &str| -> Result<std::ops::Range<usize>> {
+        let caps = re.captures(line).chain_err(|| "No matches")?;
+        let start_str = caps.get(1).chain_err(|| "Range start not found")?.as_str();
+        let start: usize = parse::value(data, start_str).chain_err(|| "Unable to parse start")?;
+        let end = match caps.get(3) {
+            Some(end_str) => {
+                parse::value(data, end_str.as_str()).chain_err(|| "Unable to parse end")?
+            }
+            None => start,
+        };
+        Ok(start..end + 1)
+    };
+
+    let mut ground = Grid::new(Square::Sand);
+    let veins = parse::lines(data, |line| {
+        Ok((parse_range(&x_re, line)?, parse_range(&y_re, line)?))
+    })?;
+    for (x_range, y_range) in veins {
+        for y in y_range {
+            for x in x_range.clone() {
+                ground.set(y as isize, x as isize, Square::Clay);
+            }
+        }
+    }
+    // water can flow down either side of the outermost clay
+    Ok(ground.freeze((0, 2)))
+}
+
+#[cfg(test)]
+mod tests {
+    const DATA: &'static str = r"x=495, y=2..7
+y=7, x=495..501
+x=501, y=3..7
+x=498, y=2..4
+x=506, y=1..2
+x=498, y=10..13
+x=504, y=10..13
+y=13, x=498..504";
+
+    #[test]
+    fn test_part_1() {
+        use super::parse_ground;
+        use super::part_1;
+
+        let (ground, origin) = parse_ground(&DATA).unwrap();
+        println!("{}", ground);
+        assert_eq!(57, part_1(&ground, origin));
+    }
+
+    #[test]
+    fn test_part_2() {
+        use super::parse_ground;
+        use super::part_2;
+
+        let (ground, origin) = parse_ground(&DATA).unwrap();
+        println!("{}", ground);
+        assert_eq!(29, part_2(&ground, origin));
+    }
+}
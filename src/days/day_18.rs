@@ -1,24 +1,48 @@
-#[macro_use]
-extern crate error_chain;
+use cycle;
+use errors::*;
+use record::{self, Recorder};
+use render::{self, Rgb};
+use solution::Solution;
+use std::fmt;
+use Mat;
 
-extern crate aoc2018;
+pub struct Day18;
 
-use aoc2018::Mat;
-use std::fmt;
-use std::io::Read;
+impl Solution for Day18 {
+    type Input = Collection;
 
-mod errors {
-    error_chain!{}
-}
+    fn parse(data: &str) -> Result<Collection> {
+        data.parse()
+    }
 
-use errors::*;
+    fn part_1(collection: &Collection) -> Result<String> {
+        Ok(part_1(collection).to_string())
+    }
 
-fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    let collection: Collection = data.parse().unwrap();
-    println!("part_1: {}", part_1(&collection));
-    println!("part_2: {}", part_2(&collection));
+    fn part_2(collection: &Collection) -> Result<String> {
+        Ok(part_2(collection).to_string())
+    }
+
+    fn extras(collection: &Collection, args: &[String]) -> Result<()> {
+        // optionally show the area after the ten minutes of part 1
+        let mut later = collection.clone();
+        for _minute in 1..=10 {
+            later = next_minute(&later);
+        }
+        if let Some(path) = render::requested_image(args)? {
+            later.save_image(path, 8, Acre::colour)?;
+        }
+        if args.iter().any(|arg| arg == "--ansi") {
+            print!(
+                "{}",
+                later.to_ansi_string(|acre| (acre.symbol(), acre.colour()))
+            );
+        }
+        if let Some(path) = record::requested(args) {
+            animate(collection, path)?;
+        }
+        Ok(())
+    }
 }
 
 fn part_1(collection_0: &Collection) -> usize {
@@ -30,60 +54,64 @@ fn part_1(collection_0: &Collection) -> usize {
 }
 
 fn part_2(collection_0: &Collection) -> usize {
+    value(&cycle::nth_hashed(collection_0.clone(), next_minute, 1_000_000_000))
+}
+
+/// Records the first few hundred minutes, which is enough to settle into a
+/// cycle
+fn animate(collection_0: &Collection, path: &str) -> Result<()> {
+    let mut recorder = Recorder::open(path, 8, Acre::colour)?;
     let mut collection = collection_0.clone();
-    let mut minute_seen = std::collections::HashMap::new();
-    let mut seen_at = Vec::new();
-    let mut minute = 0;
-    let mut cycle_start = 0;
-    let limit = 1_000_000_000;
-    seen_at.push(collection.clone());
-    while minute <= limit {
-        minute += 1;
+    recorder.record(&collection)?;
+    for _minute in 1..=600 {
         collection = next_minute(&collection);
-        seen_at.push(collection.clone());
-        if let Some(&seen_minute) = minute_seen.get(&collection) {
-            cycle_start = seen_minute;
-            break;
-        }
-        minute_seen.insert(collection.clone(), minute);
+        recorder.record(&collection)?;
     }
-    let cycle_length = minute - cycle_start;
-    let last_minute_period = (limit - minute) % cycle_length;
-    value(&seen_at[cycle_start + last_minute_period])
+    recorder.finish()
 }
 
 fn value(collection: &Collection) -> usize {
-    use Acre::*;
+    use self::Acre::*;
     let mut lumber = 0;
     let mut tree = 0;
-    for row in 0..collection.rows() {
-        for col in 0..collection.cols() {
-            match *collection.get(row, col) {
-                Lumber => lumber += 1,
-                Tree => tree += 1,
-                _ => (),
-            };
-        }
+    for acre in collection.iter() {
+        match *acre {
+            Lumber => lumber += 1,
+            Tree => tree += 1,
+            _ => (),
+        };
     }
     lumber * tree
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
-enum Acre {
+pub enum Acre {
     Open,
     Tree,
     Lumber,
 }
 
+impl Acre {
+    fn symbol(&self) -> char {
+        match *self {
+            Acre::Open => '.',
+            Acre::Tree => '|',
+            Acre::Lumber => '#',
+        }
+    }
+
+    fn colour(&self) -> Rgb {
+        match *self {
+            Acre::Open => Rgb(200, 180, 120),
+            Acre::Tree => Rgb(30, 140, 40),
+            Acre::Lumber => Rgb(110, 70, 30),
+        }
+    }
+}
+
 impl fmt::Display for Acre {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        use Acre::*;
-        let c = match self {
-            Open => '.',
-            Tree => '|',
-            Lumber => '#',
-        };
-        write!(f, "{}", c)
+        write!(f, "{}", self.symbol())
     }
 }
 
@@ -97,7 +125,7 @@ impl std::str::FromStr for Acre {
     type Err = Error;
 
     fn from_str(data: &str) -> Result<Self> {
-        use Acre::*;
+        use self::Acre::*;
         match data {
             "." => Ok(Open),
             "|" => Ok(Tree),
@@ -110,18 +138,13 @@ impl std::str::FromStr for Acre {
 type Collection = Mat<Acre>;
 
 fn next_minute(collection: &Collection) -> Collection {
-    use Acre::*;
+    use self::Acre::*;
     let mut next_collection = collection.clone();
     for row0 in 0..collection.rows() {
         for col0 in 0..collection.cols() {
             let mut adjacent = std::collections::HashMap::new();
-            for row in row0.max(1) - 1..(row0 + 2).min(collection.rows()) {
-                for col in col0.max(1) - 1..(col0 + 2).min(collection.cols()) {
-                    if row == row0 && col == col0 {
-                        continue;
-                    }
-                    *adjacent.entry(collection.get(row, col)).or_insert(0) += 1;
-                }
+            for (row, col) in collection.neighbors8(row0, col0) {
+                *adjacent.entry(collection.get(row, col)).or_insert(0) += 1;
             }
             let acre = *collection.get(row0, col0);
             let next_acre = match acre {
@@ -159,8 +182,8 @@ fn next_minute(collection: &Collection) -> Collection {
 mod tests {
     #[test]
     fn test_part_1() {
-        use part_1;
-        use Collection;
+        use super::part_1;
+        use super::Collection;
 
         let data = r".#.#...|#.
 .....#|##|
@@ -178,8 +201,8 @@ mod tests {
 
     #[test]
     fn test_next_minute() {
-        use next_minute;
-        use Collection;
+        use super::next_minute;
+        use super::Collection;
         let data = vec![
             r".#.#...|#.
 .....#|##|
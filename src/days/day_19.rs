@@ -0,0 +1,56 @@
+use elfcode;
+use errors::*;
+use solution::Solution;
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = elfcode::Machine;
+
+    fn parse(data: &str) -> Result<elfcode::Machine> {
+        Ok(data.parse()?)
+    }
+
+    fn part_1(machine: &elfcode::Machine) -> Result<String> {
+        Ok(part_1(machine)?.to_string())
+    }
+
+    fn part_2(machine: &elfcode::Machine) -> Result<String> {
+        Ok(part_2(machine)?.to_string())
+    }
+}
+
+fn part_1(state_0: &elfcode::Machine) -> elfcode::errors::Result<usize> {
+    let mut state = state_0.clone();
+    state.optimize();
+    while state.next()? {}
+    Ok(state.registers[0])
+}
+
+fn part_2(state_0: &elfcode::Machine) -> elfcode::errors::Result<usize> {
+    let mut state = state_0.clone();
+    state.optimize();
+    state.registers[0] = 1;
+    while state.next()? {}
+    Ok(state.registers[0])
+}
+
+#[cfg(test)]
+mod tests {
+    const DATA: &'static str = r"#ip 0
+seti 5 0 1
+seti 6 0 2
+addi 0 1 0
+addr 1 2 3
+setr 1 0 0
+seti 8 0 4
+seti 9 0 5";
+
+    #[test]
+    fn test_part_1() {
+        use elfcode::Machine;
+        use super::part_1;
+        let state: Machine = DATA.parse().unwrap();
+        assert_eq!(7, part_1(&state).unwrap());
+    }
+}
@@ -0,0 +1,231 @@
+use errors::*;
+use parse;
+use render::{self, Rgb};
+use search;
+use solution::Solution;
+use std::collections::HashMap;
+use std::fmt;
+use Grid;
+use Mat;
+
+pub struct Day20;
+
+/// The map of the facility, and the number of doors on the shortest path to
+/// every room
+pub type Facility = (Mat<Square>, HashMap<(usize, usize), usize>);
+
+impl Solution for Day20 {
+    type Input = Facility;
+
+    fn parse(data: &str) -> Result<Facility> {
+        shortest_paths(data)
+    }
+
+    fn part_1((_, paths): &Facility) -> Result<String> {
+        Ok(part_1(paths).to_string())
+    }
+
+    fn part_2((_, paths): &Facility) -> Result<String> {
+        Ok(part_2(paths).to_string())
+    }
+
+    fn extras((map, paths): &Facility, args: &[String]) -> Result<()> {
+        // optionally show the map with the rooms coloured by their distance
+        let furthest = part_1(paths).max(1);
+        let mut distances = Mat::new(map.rows(), map.cols(), (Square::Wall, None));
+        for row in 0..map.rows() {
+            for col in 0..map.cols() {
+                let distance = paths.get(&(row, col)).cloned();
+                distances.set(row, col, (*map.get(row, col), distance));
+            }
+        }
+        let colour = |&(square, distance): &(Square, Option<usize>)| match distance {
+            Some(distance) => {
+                Rgb(40, 80, 255).blend(Rgb(255, 40, 0), distance as f64 / furthest as f64)
+            }
+            None => square.colour(),
+        };
+        if let Some(path) = render::requested_image(args)? {
+            distances.save_image(path, 4, colour)?;
+        }
+        if args.iter().any(|arg| arg == "--ansi") {
+            print!(
+                "{}",
+                distances.to_ansi_string(|cell| (cell.0.symbol(), colour(cell)))
+            );
+        }
+        Ok(())
+    }
+}
+
+fn part_1(paths: &HashMap<(usize, usize), usize>) -> usize {
+    *paths.values().max().unwrap_or(&0)
+}
+
+fn part_2(paths: &HashMap<(usize, usize), usize>) -> usize {
+    paths.values().filter(|&&d| d >= 1000).count()
+}
+
+fn shortest_paths(pattern: &str) -> Result<Facility> {
+    use self::Square::*;
+    let (map, origin) = build_map(&pattern)?;
+    let rooms = |&(row, col): &(usize, usize)| {
+        let mut rooms = Vec::new();
+        for (i, j) in map.neighbors4(row, col) {
+            match *map.get(i, j) {
+                HDoor | VDoor => {
+                    // the room behind a door is one step further in the
+                    // same direction
+                    match ((2 * i).checked_sub(row), (2 * j).checked_sub(col)) {
+                        (Some(r), Some(c)) if r < map.rows() && c < map.cols() => {
+                            rooms.push((r, c))
+                        }
+                        _ => (),
+                    }
+                }
+                _ => (),
+            };
+        }
+        rooms
+    };
+    let distances = search::bfs(origin, rooms, |_| false).distances;
+    Ok((map, distances))
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Square {
+    Wall,
+    HDoor,
+    VDoor,
+    Room,
+    Cursor,
+}
+
+impl Square {
+    fn symbol(&self) -> char {
+        use self::Square::*;
+        match *self {
+            Wall => '#',
+            HDoor => '-',
+            VDoor => '|',
+            Room => '.',
+            Cursor => 'X',
+        }
+    }
+
+    fn colour(&self) -> Rgb {
+        use self::Square::*;
+        match *self {
+            Wall => Rgb::BLACK,
+            HDoor | VDoor => Rgb(160, 160, 160),
+            Room => Rgb::WHITE,
+            Cursor => Rgb(0, 200, 0),
+        }
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+fn build_map(pattern: &str) -> Result<(Mat<Square>, (usize, usize))> {
+    use self::Square::*;
+    let directions = pattern.trim();
+    let mut map = Grid::new(Wall);
+    map.set(0, 0, Room);
+    let mut row: isize = 0;
+    let mut col: isize = 0;
+    let mut stack = vec![(0, 0)];
+    for (i, c) in directions.char_indices() {
+        let at = || parse::at(pattern, &directions[i..]);
+        match c {
+            '(' => {
+                stack.push((row, col));
+            }
+            ')' => {
+                if stack.len() == 1 {
+                    return Err(Error::from("unbalanced ')'")).chain_err(at);
+                }
+                let next = stack.pop().unwrap();
+                row = next.0;
+                col = next.1;
+            }
+            '|' => {
+                let next = *stack.last().unwrap();
+                row = next.0;
+                col = next.1;
+            }
+            'W' => {
+                map.set(row, col - 1, VDoor);
+                map.set(row, col - 2, Room);
+                col -= 2;
+            }
+            'E' => {
+                map.set(row, col + 1, VDoor);
+                map.set(row, col + 2, Room);
+                col += 2;
+            }
+            'N' => {
+                map.set(row - 1, col, HDoor);
+                map.set(row - 2, col, Room);
+                row -= 2;
+            }
+            'S' => {
+                map.set(row + 1, col, HDoor);
+                map.set(row + 2, col, Room);
+                row += 2;
+            }
+            '^' | '$' => (),
+            _ => {
+                let e = Error::from(format!("unexpected path character '{}'", c));
+                return Err(e).chain_err(at);
+            }
+        };
+    }
+    let (mut mat, offset) = map.freeze((1, 1));
+    let origin = offset.to_mat(0, 0);
+    mat.set(origin.0, origin.1, Cursor);
+    Ok((mat, origin))
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn test_build_map() {
+        use super::build_map;
+        let pattern = "^WSSEESWWWNW(S|NENNEEEENN(ESSSSW(NWSW|SSEN)|WSWWN(E|WWS(E|SS))))$";
+        let (map, _origin) = build_map(pattern).unwrap();
+        assert_eq!(
+            r"###############
+#.|.|.|.#.|.|.#
+#-###-###-#-#-#
+#.|.#.|.|.#.#.#
+#-#########-#-#
+#.#.|.|.|.|.#.#
+#-#-#########-#
+#.#.#.|X#.|.#.#
+###-#-###-#-#-#
+#.|.#.#.|.#.|.#
+#-###-#####-###
+#.|.#.|.|.#.#.#
+#-#-#####-#-#-#
+#.#.|.|.|.#.|.#
+###############
+",
+            map.to_string()
+        );
+    }
+
+    #[test]
+    fn test_part_1() {
+        use super::part_1;
+        use super::shortest_paths;
+
+        let paths =
+            shortest_paths("^WSSEESWWWNW(S|NENNEEEENN(ESSSSW(NWSW|SSEN)|WSWWN(E|WWS(E|SS))))$");
+        assert_eq!(31, part_1(&paths.unwrap().1));
+    }
+}
@@ -0,0 +1,116 @@
+use elfcode;
+use errors::*;
+use solution::Solution;
+use std::collections::HashSet;
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = elfcode::Machine;
+
+    fn parse(data: &str) -> Result<elfcode::Machine> {
+        Ok(data.parse()?)
+    }
+
+    fn part_1(machine: &elfcode::Machine) -> Result<String> {
+        // the first value is all part 1 needs, so stop there
+        let values = halting_values(machine, 1)?;
+        let value = part_1(&values).chain_err(|| "the program never reaches its check")?;
+        Ok(value.to_string())
+    }
+
+    fn part_2(machine: &elfcode::Machine) -> Result<String> {
+        let values = halting_values(machine, usize::MAX)?;
+        let value = part_2(&values).chain_err(|| "the program never reaches its check")?;
+        Ok(value.to_string())
+    }
+}
+
+fn part_1(values: &[usize]) -> Option<usize> {
+    values.first().cloned()
+}
+
+fn part_2(values: &[usize]) -> Option<usize> {
+    values.last().cloned()
+}
+
+/// The program only halts when an `eqrr` finds some register equal to `r[0]`.
+/// Returns the line of that check and the register compared against `r[0]`.
+fn find_check(state: &elfcode::Machine) -> Option<(usize, usize)> {
+    state
+        .instructions
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _))| op.name() == "eqrr")
+        .filter_map(|(line, &(_, (a, b, _)))| match (a, b) {
+            (0, 0) => None,
+            (0, other) | (other, 0) => Some((line, other)),
+            _ => None,
+        })
+        .next()
+}
+
+/// The values of `r[0]` that make the program halt, in the order in which the
+/// check sees them, up to the point where they start repeating or there are
+/// `limit` of them.
+fn halting_values(state_0: &elfcode::Machine, limit: usize) -> Result<Vec<usize>> {
+    let (check, register) = match find_check(state_0) {
+        Some(check) => check,
+        None => bail!("the program never compares a register against r[0]"),
+    };
+    let mut state = state_0.clone();
+    state.optimize();
+    let mut seen = HashSet::new();
+    let mut values = Vec::new();
+    while values.len() < limit
+        && state.run_until(usize::MAX, |m| m.line() == check)? == elfcode::Stop::Predicate
+    {
+        let value = state.registers[register];
+        if !seen.insert(value) {
+            break;
+        }
+        values.push(value);
+        state.step()?;
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_halting_values() {
+        use elfcode::Machine;
+        use super::find_check;
+        use super::halting_values;
+        let data = r"#ip 1
+seti 0 0 2
+addi 2 3 2
+bani 2 7 2
+eqrr 2 0 3
+addr 3 1 1
+seti 0 0 1";
+        let state: Machine = data.parse().unwrap();
+        assert_eq!(Some((3, 2)), find_check(&state));
+        assert_eq!(
+            vec![3, 6, 1, 4, 7, 2, 5, 0],
+            halting_values(&state, usize::MAX).unwrap()
+        );
+        assert_eq!(vec![3, 6], halting_values(&state, 2).unwrap());
+
+        let state: Machine = "#ip 1\nseti 0 0 2".parse().unwrap();
+        assert!(halting_values(&state, usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_halts_before_check() {
+        use super::{halting_values, part_1, part_2};
+        use elfcode::Machine;
+        let data = r"#ip 1
+seti 9 0 1
+eqrr 2 0 3";
+        let state: Machine = data.parse().unwrap();
+        let values = halting_values(&state, usize::MAX).unwrap();
+        assert_eq!(None, part_1(&values));
+        assert_eq!(None, part_2(&values));
+    }
+}
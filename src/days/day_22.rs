@@ -1,40 +1,41 @@
-extern crate regex;
-
-use std::cmp::Reverse;
-use std::collections::{BinaryHeap, HashMap};
+use errors::*;
+use parse;
+use search;
+use solution::Solution;
+use std::collections::HashMap;
 use std::fmt;
-use std::io::Read;
 
-fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = (usize, (usize, usize));
+
+    fn parse(data: &str) -> Result<(usize, (usize, usize))> {
+        parse_scan(data)
+    }
+
+    fn part_1(scan: &(usize, (usize, usize))) -> Result<String> {
+        let &(depth, target) = scan;
+        Ok(part_1(&mut CaveSystem::new(depth, target)).to_string())
+    }
+
+    fn part_2(scan: &(usize, (usize, usize))) -> Result<String> {
+        let &(depth, target) = scan;
+        Ok(part_2(&mut CaveSystem::new(depth, target)).to_string())
+    }
+}
+
+/// The depth and the `(row, col)` of the target
+fn parse_scan(data: &str) -> Result<(usize, (usize, usize))> {
     let re = regex::Regex::new(
         r"^depth: (?P<depth>\d+)\s+target: (?P<target_x>[\d]+),(?P<target_y>[\d]+)\s*$",
     )
     .unwrap();
-    let caps = re.captures(&data).expect("unable to parse input");
-    let depth: usize = caps
-        .name("depth")
-        .expect("unable to find depth")
-        .as_str()
-        .parse()
-        .expect("unable to parse depth");
-    let target_x: usize = caps
-        .name("target_x")
-        .expect("unable to find target_x")
-        .as_str()
-        .parse()
-        .expect("unable to parse target_x");
-    let target_y: usize = caps
-        .name("target_y")
-        .expect("unable to find target_y")
-        .as_str()
-        .parse()
-        .expect("unable to parse target_y");
-    let target = (target_y, target_x);
-    let mut cs = CaveSystem::new(depth, target);
-    println!("part_1: {}", part_1(&mut cs));
-    println!("part_2: {}", part_2(&mut cs));
+    let caps = re.captures(data).chain_err(|| "unable to parse scan")?;
+    let depth = parse::value(data, &caps["depth"]).chain_err(|| "unable to parse depth")?;
+    let target_x = parse::value(data, &caps["target_x"]).chain_err(|| "unable to parse target_x")?;
+    let target_y = parse::value(data, &caps["target_y"]).chain_err(|| "unable to parse target_y")?;
+    Ok((depth, (target_y, target_x)))
 }
 
 fn part_1(cs: &mut CaveSystem) -> usize {
@@ -60,7 +61,7 @@ enum Terrain {
 
 impl Terrain {
     fn risk(&self) -> usize {
-        use Terrain::*;
+        use self::Terrain::*;
         match self {
             Rocky => 0,
             Wet => 1,
@@ -68,8 +69,8 @@ impl Terrain {
         }
     }
     fn tools(&self) -> [Tool; 2] {
-        use Terrain::*;
-        use Tool::*;
+        use self::Terrain::*;
+        use self::Tool::*;
         match self {
             Rocky => [ClimbingGear, Torch],
             Wet => [ClimbingGear, Neither],
@@ -80,7 +81,7 @@ impl Terrain {
 
 impl fmt::Display for Terrain {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        use Terrain::*;
+        use self::Terrain::*;
         let c = match self {
             Rocky => '.',
             Wet => '=',
@@ -147,7 +148,7 @@ impl CaveSystem {
     }
 
     fn terrain_at(&mut self, row: usize, col: usize) -> Terrain {
-        use Terrain::*;
+        use self::Terrain::*;
         match self.erosion_at(row, col) % 3 {
             0 => Rocky,
             1 => Wet,
@@ -157,48 +158,44 @@ impl CaveSystem {
     }
 
     fn shortest_distance(&mut self) -> usize {
-        use Tool::*;
-        // binary heaps are max heaps, so we need Reverse values
-        let mut distances = HashMap::new();
-        let mut queue = BinaryHeap::new();
-        queue.push((Reverse(0), 0, 0, Torch));
-        distances.insert((0, 0, Torch), 0);
-        while !queue.is_empty() {
-            let (Reverse(d), row, col, tool) = queue.pop().unwrap();
-            if ((row, col), tool) == (self.target, Torch) {
-                return d;
-            }
-            let terrain = self.terrain_at(row, col);
-            let mut neighbors = Vec::new();
-            for nrow in row.max(1) - 1..=row + 1 {
-                if nrow == row {
-                    continue;
-                }
-                neighbors.push((nrow, col));
-            }
-            for ncol in col.max(1) - 1..=col + 1 {
-                if ncol == col {
-                    continue;
-                }
-                neighbors.push((row, ncol));
-            }
-            for &(nrow, ncol) in neighbors.iter() {
-                let nterrain = self.terrain_at(nrow, ncol);
-                for &ntool in terrain.tools().iter() {
-                    let neighbor = (nrow, ncol, ntool);
-                    if !ntool.can_enter(nterrain) {
-                        continue;
-                    }
-                    let new_distance = if ntool == tool { d + 1 } else { d + 8 };
-                    let old_distance = *distances.get(&neighbor).unwrap_or(&(new_distance + 1));
-                    if new_distance < old_distance {
-                        distances.insert(neighbor, new_distance);
-                        queue.push((Reverse(new_distance), nrow, ncol, ntool));
-                    }
+        use self::Tool::*;
+        let target = self.target;
+        let search = search::astar(
+            (0, 0, Torch),
+            |&(row, col, tool)| self.moves(row, col, tool),
+            |&(row, col, tool)| {
+                // at least the manhattan distance, and a tool switch
+                let remaining = (row as isize - target.0 as isize).abs()
+                    + (col as isize - target.1 as isize).abs();
+                remaining as usize + if tool == Torch { 0 } else { 7 }
+            },
+            |&(row, col, tool)| ((row, col), tool) == (target, Torch),
+        );
+        search.goal.and_then(|goal| search.distance(&goal)).unwrap()
+    }
+
+    /// Moves to an adjacent region, with the time they take, switching to
+    /// any tool usable in both regions
+    fn moves(&mut self, row: usize, col: usize, tool: Tool) -> Vec<((usize, usize, Tool), usize)> {
+        let terrain = self.terrain_at(row, col);
+        let mut neighbors = vec![(row + 1, col), (row, col + 1)];
+        if row > 0 {
+            neighbors.push((row - 1, col));
+        }
+        if col > 0 {
+            neighbors.push((row, col - 1));
+        }
+        let mut moves = Vec::new();
+        for (nrow, ncol) in neighbors {
+            let nterrain = self.terrain_at(nrow, ncol);
+            for &ntool in terrain.tools().iter() {
+                if ntool.can_enter(nterrain) {
+                    let time = if ntool == tool { 1 } else { 8 };
+                    moves.push(((nrow, ncol, ntool), time));
                 }
             }
         }
-        unreachable!()
+        moves
     }
 }
 
@@ -206,8 +203,8 @@ impl CaveSystem {
 mod tests {
     #[test]
     fn test_part_1() {
-        use part_1;
-        use CaveSystem;
+        use super::part_1;
+        use super::CaveSystem;
         let mut cs = CaveSystem::new(510, (10, 10));
         assert_eq!(114, part_1(&mut cs));
         assert_eq!(cs.erosion_at(0, 1), 17317);
@@ -217,8 +214,8 @@ mod tests {
 
     #[test]
     fn test_part_2() {
-        use part_2;
-        use CaveSystem;
+        use super::part_2;
+        use super::CaveSystem;
         let mut cs = CaveSystem::new(510, (10, 10));
         assert_eq!(45, part_2(&mut cs));
     }
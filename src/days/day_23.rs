@@ -1,25 +1,32 @@
-extern crate nalgebra as na;
-extern crate regex;
-
+use errors::*;
 use na::Vector3;
+use parse;
+use solution::Solution;
 use std::collections::BTreeSet;
-use std::io::Read;
 use std::iter::FromIterator;
 
-fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    let nanobots = parse_nanobots(&data).unwrap();
-    println!("part_1: {}", part_1(&nanobots).unwrap());
-    println!("part_2: {:?}", part_2(&nanobots));
-}
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Vec<Nanobot>;
+
+    fn parse(data: &str) -> Result<Vec<Nanobot>> {
+        parse_nanobots(data)
+    }
 
-fn parse_nanobots(data: &str) -> Result<Vec<Nanobot>, &'static str> {
-    let mut out = Vec::new();
-    for line in data.lines() {
-        out.push(line.parse()?);
+    fn part_1(bots: &Vec<Nanobot>) -> Result<String> {
+        let count = part_1(bots).chain_err(|| "there are no nanobots")?;
+        Ok(count.to_string())
+    }
+
+    fn part_2(bots: &Vec<Nanobot>) -> Result<String> {
+        let distance = part_2(bots).chain_err(|| "no position is in range of most nanobots")?;
+        Ok(distance.to_string())
     }
-    Ok(out)
+}
+
+fn parse_nanobots(data: &str) -> Result<Vec<Nanobot>> {
+    parse::lines(data, |line| parse_nanobot(data, line))
 }
 
 fn part_1(bots: &Vec<Nanobot>) -> Option<usize> {
@@ -133,7 +140,7 @@ fn recursive_scanline(
 }
 
 #[derive(Debug, Clone)]
-struct Nanobot {
+pub struct Nanobot {
     pos: Vector3<isize>,
     rad: isize,
 }
@@ -148,41 +155,18 @@ impl Nanobot {
     }
 }
 
-impl std::str::FromStr for Nanobot {
-    type Err = &'static str;
-
-    fn from_str(data: &str) -> Result<Self, Self::Err> {
-        let re = regex::Regex::new(r"^pos=<(-?\d+),(-?\d+),(-?\d+)>, r=(\d+)$").unwrap();
-        let caps = re.captures(data).ok_or("invalid nanobot input")?;
-        let x = caps
-            .get(1)
-            .ok_or("missing x")?
-            .as_str()
-            .parse()
-            .map_err(|_| "unable to parse x")?;
-        let y = caps
-            .get(2)
-            .ok_or("missing y")?
-            .as_str()
-            .parse()
-            .map_err(|_| "unable to parse y")?;
-        let z = caps
-            .get(3)
-            .ok_or("missing z")?
-            .as_str()
-            .parse()
-            .map_err(|_| "unable to parse z")?;
-        let rad = caps
-            .get(4)
-            .ok_or("missing rad")?
-            .as_str()
-            .parse()
-            .map_err(|_| "unable to parse rad")?;
-        Ok(Nanobot {
-            pos: Vector3::new(x, y, z),
-            rad: rad,
-        })
-    }
+/// Parses `line`, a slice of `data`
+fn parse_nanobot(data: &str, line: &str) -> Result<Nanobot> {
+    let re = regex::Regex::new(r"^pos=<(-?\d+),(-?\d+),(-?\d+)>, r=(\d+)$").unwrap();
+    let caps = re.captures(line).chain_err(|| "invalid nanobot input")?;
+    let x = parse::value(data, &caps[1]).chain_err(|| "unable to parse x")?;
+    let y = parse::value(data, &caps[2]).chain_err(|| "unable to parse y")?;
+    let z = parse::value(data, &caps[3]).chain_err(|| "unable to parse z")?;
+    let rad = parse::value(data, &caps[4]).chain_err(|| "unable to parse rad")?;
+    Ok(Nanobot {
+        pos: Vector3::new(x, y, z),
+        rad: rad,
+    })
 }
 
 #[cfg(test)]
@@ -190,8 +174,8 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        use parse_nanobots;
-        use part_1;
+        use super::parse_nanobots;
+        use super::part_1;
         let input = r"pos=<0,0,0>, r=4
 pos=<1,0,0>, r=1
 pos=<4,0,0>, r=3
@@ -207,8 +191,8 @@ pos=<1,3,1>, r=1";
 
     #[test]
     fn test_part_2() {
-        use parse_nanobots;
-        use part_2;
+        use super::parse_nanobots;
+        use super::part_2;
         let input = r"pos=<10,12,12>, r=2
 pos=<12,14,12>, r=2
 pos=<16,12,12>, r=4
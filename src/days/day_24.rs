@@ -1,18 +1,29 @@
-extern crate regex;
-
+use errors::*;
+use parse;
 use regex::Regex;
+use solution::Solution;
 use std::cmp::Reverse;
 use std::collections::HashSet;
 use std::fmt;
-use std::io::Read;
 use std::iter::FromIterator;
 
-fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    let state: State = data.parse().unwrap();
-    println!("part_1: {}", part_1(&state));
-    println!("part_2: {}", part_2(&state).1);
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input = State;
+
+    fn parse(data: &str) -> Result<State> {
+        data.parse()
+    }
+
+    fn part_1(state: &State) -> Result<String> {
+        Ok(part_1(state).to_string())
+    }
+
+    fn part_2(state: &State) -> Result<String> {
+        let (_, units) = part_2(state);
+        Ok(units.to_string())
+    }
 }
 
 fn part_1(state_0: &State) -> usize {
@@ -101,7 +112,7 @@ impl UnitGroup {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
-struct State {
+pub struct State {
     groups: Vec<UnitGroup>,
 }
 
@@ -227,9 +238,9 @@ impl State {
 }
 
 impl std::str::FromStr for State {
-    type Err = &'static str;
+    type Err = Error;
 
-    fn from_str(data: &str) -> Result<Self, Self::Err> {
+    fn from_str(data: &str) -> Result<Self> {
         let outer_re =
             Regex::new("(?P<unit_type>Immune System|Infection):\n(?P<groups>(.\n?)+)").unwrap();
         let inner_re = Regex::new(r"(?P<unit_count>\d+) units each with (?P<hp>\d+) hit points (?P<specialties>\(.*\) )?with an attack that does (?P<dmg>\d+) (?P<attack_type>\w+) damage at initiative (?P<initiative>\d+)").unwrap();
@@ -237,36 +248,21 @@ impl std::str::FromStr for State {
         let weaknesses_re = Regex::new(r"weak to ([^);]+)").unwrap();
         let mut state = State { groups: Vec::new() };
         for outer_caps in outer_re.captures_iter(data) {
-            let unit_type_str = outer_caps.name("unit_type").ok_or("no unit_type")?.as_str();
-            let groups_str = outer_caps.name("groups").ok_or("no groups")?.as_str();
+            let unit_type_str = outer_caps.name("unit_type").chain_err(|| "no unit_type")?.as_str();
+            let groups_str = outer_caps.name("groups").chain_err(|| "no groups")?.as_str();
             let unit_type = match unit_type_str {
                 "Immune System" => UnitType::ImmuneSystem,
                 "Infection" => UnitType::Infection,
-                _ => return Err("invalid unit type"),
+                _ => bail!(parse::at(data, unit_type_str)),
             };
             for (i, inner_caps) in inner_re.captures_iter(groups_str).enumerate() {
-                let unit_count: usize = inner_caps
-                    .name("unit_count")
-                    .ok_or("no unit_count")?
-                    .as_str()
-                    .parse()
-                    .map_err(|_| "invalid unit_count")?;
-                let hit_points: usize = inner_caps
-                    .name("hp")
-                    .ok_or("no hp")?
-                    .as_str()
-                    .parse()
-                    .map_err(|_| "invalid hp")?;
-                let attack_type = inner_caps
-                    .name("attack_type")
-                    .ok_or("no attack type")?
-                    .as_str();
-                let attack_damage: usize = inner_caps
-                    .name("dmg")
-                    .ok_or("no dmg")?
-                    .as_str()
-                    .parse()
-                    .map_err(|_| "invalid dmg")?;
+                let unit_count: usize =
+                    parse::value(data, &inner_caps["unit_count"]).chain_err(|| "invalid unit_count")?;
+                let hit_points: usize =
+                    parse::value(data, &inner_caps["hp"]).chain_err(|| "invalid hp")?;
+                let attack_type = &inner_caps["attack_type"];
+                let attack_damage: usize =
+                    parse::value(data, &inner_caps["dmg"]).chain_err(|| "invalid dmg")?;
 
                 let mut immunities = HashSet::new();
                 let mut weaknesses = HashSet::new();
@@ -293,12 +289,8 @@ impl std::str::FromStr for State {
                         );
                     }
                 }
-                let initiative: usize = inner_caps
-                    .name("initiative")
-                    .ok_or("no initiative")?
-                    .as_str()
-                    .parse()
-                    .map_err(|_| "invalid initiative")?;
+                let initiative: usize =
+                    parse::value(data, &inner_caps["initiative"]).chain_err(|| "invalid initiative")?;
                 let group = UnitGroup {
                     id: i + 1,
                     unit_type: unit_type,
@@ -352,8 +344,8 @@ impl fmt::Display for UnitType {
 mod tests {
     #[test]
     fn test_part_1() {
-        use part_1;
-        use State;
+        use super::part_1;
+        use super::State;
         let input = r"Immune System:
 17 units each with 5390 hit points (weak to radiation, bludgeoning) with an attack that does 4507 fire damage at initiative 2
 989 units each with 1274 hit points (immune to fire; weak to bludgeoning, slashing) with an attack that does 25 slashing damage at initiative 3
@@ -367,8 +359,8 @@ Infection:
 
     #[test]
     fn test_part_2() {
-        use part_2;
-        use State;
+        use super::part_2;
+        use super::State;
         let input = r"Immune System:
 17 units each with 5390 hit points (weak to radiation, bludgeoning) with an attack that does 4507 fire damage at initiative 2
 989 units each with 1274 hit points (immune to fire; weak to bludgeoning, slashing) with an attack that does 25 slashing damage at initiative 3
@@ -1,12 +1,26 @@
+use errors::*;
+use parse;
+use solution::Solution;
 use std::collections::BTreeSet;
-use std::io::Read;
 use std::iter::FromIterator;
 
-fn main() {
-    let mut data = String::new();
-    std::io::stdin().read_to_string(&mut data).unwrap();
-    let points = parse_points(&data).unwrap();
-    println!("part_1: {}", part_1(&points));
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = Vec<Point>;
+
+    fn parse(data: &str) -> Result<Vec<Point>> {
+        parse_points(data)
+    }
+
+    fn part_1(points: &Vec<Point>) -> Result<String> {
+        Ok(part_1(points).to_string())
+    }
+
+    fn part_2(_points: &Vec<Point>) -> Result<String> {
+        // the last day has only one puzzle
+        Ok(String::new())
+    }
 }
 
 fn part_1(points: &Vec<Point>) -> usize {
@@ -14,11 +28,14 @@ fn part_1(points: &Vec<Point>) -> usize {
     graph.constellation_count()
 }
 
-fn parse_points(data: &str) -> Result<Vec<Point>, std::num::ParseIntError> {
-    return data.lines().map(|line| {
-        let values: Vec<_> = line.split(",").map(|w| w.parse::<isize>()).collect::<Result<Vec<_>,_>>()?;
+fn parse_points(data: &str) -> Result<Vec<Point>> {
+    return parse::lines(data, |line| {
+        let values: Vec<_> = line.split(",").map(|w| parse::value(data, w)).collect::<Result<Vec<_>>>()?;
+        if values.len() != 4 {
+            bail!("expected 4 coordinates, found {}", values.len());
+        }
         Ok([values[0], values[1], values[2], values[3]])
-    }).collect()
+    });
 }
 
 type Point = [isize; 4];
@@ -119,8 +136,8 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        use parse_points;
-        use part_1;
+        use super::parse_points;
+        use super::part_1;
         for (data, expected_result) in TEST_CASES.iter() {
             let points = parse_points(data).unwrap();
             assert_eq!(*expected_result, part_1(&points));
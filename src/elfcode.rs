@@ -1,29 +1,304 @@
-pub type Args = (usize, usize, usize);
-pub type Registers = [usize; 6];
-pub type Instruction = (Operation, Args);
-pub type OperationFn = fn(Args, Registers) -> Registers;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::Hash;
+use std::ops::{BitAnd, BitOr};
 
+mod asm;
+mod debug;
+mod decompile;
+mod fast;
+mod opcodes;
+mod optimize;
+mod profile;
+
+pub use self::asm::assemble;
+pub use self::debug::{Debugger, Step, Stop};
+pub use self::fast::Interpreter;
+pub use self::opcodes::{infer_opcodes, Sample};
+pub use self::optimize::Idiom;
+pub use self::profile::Profile;
+use self::errors::*;
+
+pub mod errors {
+    use super::Fault;
+    use std::collections::BTreeMap;
+
+    error_chain! {
+        errors {
+            Syntax(line: usize, col: usize, msg: String) {
+                description("elfcode syntax error"),
+                display("{}:{}: {}", line, col, msg)
+            }
+
+            AmbiguousOpcodes(candidates: BTreeMap<usize, Vec<&'static str>>) {
+                description("unable to infer opcodes"),
+                display("unable to infer opcodes, remaining candidates: {:?}", candidates)
+            }
+
+            Fault(line: usize, instruction: String, fault: Fault) {
+                description("elfcode execution fault"),
+                display("{}: {}: {}", line, instruction, fault)
+            }
+        }
+    }
+}
+
+pub const REGISTER_COUNT: usize = 6;
+
+pub type Args<W = usize> = (W, W, W);
+pub type Registers<W = usize, const N: usize = REGISTER_COUNT> = [W; N];
+pub type Instruction<W = usize, const N: usize = REGISTER_COUNT> = (Operation<W, N>, Args<W>);
+pub type OperationFn<W = usize, const N: usize = REGISTER_COUNT> =
+    fn(Args<W>, Registers<W, N>, Arithmetic) -> Outcome<W, N>;
+pub type Outcome<W = usize, const N: usize = REGISTER_COUNT> =
+    ::std::result::Result<Registers<W, N>, Fault>;
+
+/// What `addi`, `addr`, `muli` and `mulr` do when the result does not fit in
+/// a word
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
-pub struct Operation(&'static str, OperationFn);
+pub enum Arithmetic {
+    /// Fail with `Fault::Overflow`
+    Checked,
+    Wrapping,
+    Saturating,
+}
+
+impl Default for Arithmetic {
+    fn default() -> Arithmetic {
+        Arithmetic::Checked
+    }
+}
+
+/// Why an instruction could not be executed
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Fault {
+    /// Checked arithmetic overflowed
+    Overflow,
+    /// The argument at this position (0 for `a` to 2 for `c`) names a
+    /// register that does not exist
+    InvalidRegister(usize),
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Fault::Overflow => write!(f, "arithmetic overflow"),
+            Fault::InvalidRegister(position) => write!(
+                f,
+                "argument {} is not a register",
+                ["a", "b", "c"][position]
+            ),
+        }
+    }
+}
+
+/// The values held by registers. Register arguments are words as well, and
+/// refer to the register at their index.
+pub trait Word:
+    Copy
+    + Eq
+    + Ord
+    + Hash
+    + Default
+    + fmt::Debug
+    + fmt::Display
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// `self + other`, or `None` if it overflows in checked mode
+    fn add_with(self, other: Self, arithmetic: Arithmetic) -> Option<Self>;
+    /// `self * other`, or `None` if it overflows in checked mode
+    fn mul_with(self, other: Self, arithmetic: Arithmetic) -> Option<Self>;
+    /// The register this word refers to, if it is a valid index at all
+    fn index(self) -> Option<usize>;
+}
+
+macro_rules! impl_word {
+    ($($t:ty),*) => {
+        $(
+            impl Word for $t {
+                const ZERO: $t = 0;
+                const ONE: $t = 1;
+
+                fn add_with(self, other: $t, arithmetic: Arithmetic) -> Option<$t> {
+                    match arithmetic {
+                        Arithmetic::Checked => self.checked_add(other),
+                        Arithmetic::Wrapping => Some(self.wrapping_add(other)),
+                        Arithmetic::Saturating => Some(self.saturating_add(other)),
+                    }
+                }
+
+                fn mul_with(self, other: $t, arithmetic: Arithmetic) -> Option<$t> {
+                    match arithmetic {
+                        Arithmetic::Checked => self.checked_mul(other),
+                        Arithmetic::Wrapping => Some(self.wrapping_mul(other)),
+                        Arithmetic::Saturating => Some(self.saturating_mul(other)),
+                    }
+                }
+
+                fn index(self) -> Option<usize> {
+                    usize::try_from(self).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_word!(usize, u32, u64, i32, i64);
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Operation<W = usize, const N: usize = REGISTER_COUNT>(&'static str, OperationFn<W, N>);
+
+impl<W: Word, const N: usize> Operation<W, N> {
+    /// All sixteen operations, in no particular order
+    pub const OPS: [Operation<W, N>; 16] = [
+        Operation("addi", addi),
+        Operation("addr", addr),
+        Operation("muli", muli),
+        Operation("mulr", mulr),
+        Operation("bani", bani),
+        Operation("banr", banr),
+        Operation("bori", bori),
+        Operation("borr", borr),
+        Operation("seti", seti),
+        Operation("setr", setr),
+        Operation("gtir", gtir),
+        Operation("gtri", gtri),
+        Operation("gtrr", gtrr),
+        Operation("eqir", eqir),
+        Operation("eqri", eqri),
+        Operation("eqrr", eqrr),
+    ];
+
+    /// The registers after executing the operation on `registers`
+    pub fn execute(
+        &self,
+        args: Args<W>,
+        registers: Registers<W, N>,
+        arithmetic: Arithmetic,
+    ) -> Outcome<W, N> {
+        (self.1)(args, registers, arithmetic)
+    }
+}
+
+/// How an instruction interprets each of its three arguments
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Operand {
+    Register,
+    Immediate,
+    Ignored,
+}
+
+impl<W, const N: usize> Operation<W, N> {
+    pub fn name(&self) -> &'static str {
+        self.0
+    }
+
+    pub fn operands(&self) -> [Operand; 3] {
+        use self::Operand::*;
+        match self.0 {
+            "seti" => [Immediate, Ignored, Register],
+            "setr" => [Register, Ignored, Register],
+            "gtir" | "eqir" => [Immediate, Register, Register],
+            "addi" | "muli" | "bani" | "bori" | "gtri" | "eqri" => [Register, Immediate, Register],
+            _ => [Register, Register, Register],
+        }
+    }
+}
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
 pub struct Machine {
     pub instruction_pointer: usize,
     pub instructions: Vec<Instruction>,
     pub registers: Registers,
+    pub arithmetic: Arithmetic,
+    /// Loops to execute natively, by the index of their first instruction.
+    /// Filled in by `optimize`.
+    pub idioms: Vec<Option<Idiom>>,
+    /// Collected by `step` once `enable_profiling` has been called
+    pub profile: Option<Profile>,
 }
 
 impl Machine {
-    pub fn next(&mut self) -> bool {
-        if let Some(&(op, args)) = self
-            .instructions
-            .get(self.registers[self.instruction_pointer])
-        {
-            self.registers = op.1(args, self.registers);
-            self.registers[self.instruction_pointer] += 1;
-            true
-        } else {
-            false
+    /// Executes one instruction, returning `false` once the machine has
+    /// halted. Use `step` to see what the instruction did.
+    pub fn next(&mut self) -> Result<bool> {
+        Ok(self.step()?.is_some())
+    }
+
+    /// The index of the instruction that executes next
+    pub fn line(&self) -> usize {
+        self.registers[self.instruction_pointer]
+    }
+
+    /// Executes one instruction, or returns `None` if the machine has halted.
+    /// On a fault the registers are left as they were before the instruction.
+    pub fn step(&mut self) -> Result<Option<Step>> {
+        let line = self.line();
+        let instruction = match self.instructions.get(line) {
+            None => return Ok(None),
+            Some(&instruction) => instruction,
+        };
+        let before = self.registers;
+        let ip = self.instruction_pointer;
+        let arithmetic = self.arithmetic;
+        let mut registers = self.registers;
+        let native = match self.idioms.get(line) {
+            Some(&Some(idiom)) => idiom.apply(&mut registers, ip, arithmetic),
+            _ => Ok(false),
+        };
+        let outcome = match native {
+            Ok(true) => Ok(registers),
+            Err(fault) => Err(fault),
+            Ok(false) => {
+                let (op, args) = instruction;
+                op.execute(args, self.registers, arithmetic)
+                    .and_then(|mut registers| {
+                        registers[ip] = registers[ip]
+                            .add_with(1, arithmetic)
+                            .ok_or(Fault::Overflow)?;
+                        Ok(registers)
+                    })
+            }
+        };
+        self.registers = outcome.map_err(|fault| {
+            let (op, (a, b, c)) = instruction;
+            let text = format!("{} {} {} {}", op.name(), a, b, c);
+            Error::from(ErrorKind::Fault(line, text, fault))
+        })?;
+        let step = Step {
+            line,
+            instruction,
+            before,
+            after: self.registers,
+        };
+        if let Some(ref mut profile) = self.profile {
+            profile.record(&step);
+        }
+        Ok(Some(step))
+    }
+
+    /// Runs until `predicate` holds before an instruction, the machine halts,
+    /// or `budget` instructions have been executed.
+    pub fn run_until<P>(&mut self, budget: usize, mut predicate: P) -> Result<Stop>
+    where
+        P: FnMut(&Machine) -> bool,
+    {
+        let mut executed = 0;
+        loop {
+            if predicate(self) {
+                return Ok(Stop::Predicate);
+            }
+            if executed == budget {
+                return Ok(Stop::Budget);
+            }
+            if self.step()?.is_none() {
+                return Ok(Stop::Halted);
+            }
+            executed += 1;
         }
     }
 
@@ -42,28 +317,27 @@ impl Machine {
     }
 }
 
-fn parse_operation(data: &str) -> Result<Operation, &'static str> {
-    match data {
-        "addi" => Ok(Operation("addi", addi)),
-        "addr" => Ok(Operation("addr", addr)),
-        "muli" => Ok(Operation("muli", muli)),
-        "mulr" => Ok(Operation("mulr", mulr)),
-        "bani" => Ok(Operation("bani", bani)),
-        "banr" => Ok(Operation("banr", banr)),
-        "bori" => Ok(Operation("bori", bori)),
-        "borr" => Ok(Operation("borr", borr)),
-        "seti" => Ok(Operation("seti", seti)),
-        "setr" => Ok(Operation("setr", setr)),
-        "gtir" => Ok(Operation("gtir", gtir)),
-        "gtri" => Ok(Operation("gtri", gtri)),
-        "gtrr" => Ok(Operation("gtrr", gtrr)),
-        "eqir" => Ok(Operation("eqir", eqir)),
-        "eqri" => Ok(Operation("eqri", eqri)),
-        "eqrr" => Ok(Operation("eqrr", eqrr)),
-        _ => Err("Invalid operation"),
+/// Prints the `#ip` header and instructions in puzzle-input syntax. The
+/// registers, arithmetic and idioms are left out, so only the program
+/// survives a round trip through `parse`.
+impl fmt::Display for Machine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "#ip {}", self.instruction_pointer)?;
+        for &(op, (a, b, c)) in self.instructions.iter() {
+            writeln!(f, "{} {} {} {}", op.name(), a, b, c)?;
+        }
+        Ok(())
     }
 }
 
+fn parse_operation(data: &str) -> ::std::result::Result<Operation, &'static str> {
+    Operation::OPS
+        .iter()
+        .find(|op| op.name() == data)
+        .cloned()
+        .ok_or("Invalid operation")
+}
+
 // This function is not used in the final solution, but I used it to deparse the input for eyeballing
 fn deparse_instruction(line: usize, ip: usize, (op, (a, b, c)): Instruction) -> String {
     let r = |i| {
@@ -102,119 +376,226 @@ fn deparse_instruction(line: usize, ip: usize, (op, (a, b, c)): Instruction) ->
 }
 
 impl std::str::FromStr for Machine {
-    type Err = &'static str;
-
-    fn from_str(data: &str) -> Result<Self, Self::Err> {
-        let mut machine = Machine::default();
-        let mut lines = data.lines();
-        let ip_s = lines.next().ok_or("missing instruction pointer line")?;
-        machine.instruction_pointer = ip_s[4..]
-            .parse()
-            .map_err(|_| "invalid instruction pointer")?;
-        for line in lines {
-            let line_parts: Vec<_> = line.split(" ").collect();
-            let op = parse_operation(line_parts.get(0).ok_or("no operation in line")?)?;
-            let args: Args = (
-                line_parts
-                    .get(1)
-                    .ok_or("arg 0 missing")?
-                    .parse()
-                    .map_err(|_| "invalid arg")?,
-                line_parts
-                    .get(2)
-                    .ok_or("arg 1 missing")?
-                    .parse()
-                    .map_err(|_| "invalid arg")?,
-                line_parts
-                    .get(3)
-                    .ok_or("arg 2 missing")?
-                    .parse()
-                    .map_err(|_| "invalid arg")?,
-            );
-            machine.instructions.push((op, args));
-        }
-        Ok(machine)
+    type Err = Error;
+
+    fn from_str(data: &str) -> Result<Self> {
+        assemble(data)
     }
 }
 
-fn addi((a, b, c): Args, r: Registers) -> Registers {
-    let mut out = r.clone();
-    out[c] = r[a] + b;
-    out
+fn read<W: Word, const N: usize>(
+    r: &Registers<W, N>,
+    arg: W,
+    position: usize,
+) -> ::std::result::Result<W, Fault> {
+    arg.index()
+        .and_then(|i| r.get(i).cloned())
+        .ok_or(Fault::InvalidRegister(position))
 }
 
-fn addr((a, b, c): Args, r: Registers) -> Registers {
-    addi((a, r[b], c), r)
+fn write<W: Word, const N: usize>(mut r: Registers<W, N>, c: W, value: W) -> Outcome<W, N> {
+    match c.index().and_then(|i| r.get_mut(i)) {
+        Some(out) => *out = value,
+        None => return Err(Fault::InvalidRegister(2)),
+    }
+    Ok(r)
 }
 
-fn muli((a, b, c): Args, r: Registers) -> Registers {
-    let mut out = r.clone();
-    out[c] = r[a] * b;
-    out
+fn flag<W: Word>(condition: bool) -> W {
+    if condition {
+        W::ONE
+    } else {
+        W::ZERO
+    }
+}
+
+fn addi<W: Word, const N: usize>(
+    (a, b, c): Args<W>,
+    r: Registers<W, N>,
+    m: Arithmetic,
+) -> Outcome<W, N> {
+    let sum = read(&r, a, 0)?.add_with(b, m).ok_or(Fault::Overflow)?;
+    write(r, c, sum)
+}
+
+fn addr<W: Word, const N: usize>(
+    (a, b, c): Args<W>,
+    r: Registers<W, N>,
+    m: Arithmetic,
+) -> Outcome<W, N> {
+    addi((a, read(&r, b, 1)?, c), r, m)
 }
 
-fn mulr((a, b, c): Args, r: Registers) -> Registers {
-    muli((a, r[b], c), r)
+fn muli<W: Word, const N: usize>(
+    (a, b, c): Args<W>,
+    r: Registers<W, N>,
+    m: Arithmetic,
+) -> Outcome<W, N> {
+    let product = read(&r, a, 0)?.mul_with(b, m).ok_or(Fault::Overflow)?;
+    write(r, c, product)
 }
 
-fn bani((a, b, c): Args, r: Registers) -> Registers {
-    let mut out = r.clone();
-    out[c] = r[a] & b;
-    out
+fn mulr<W: Word, const N: usize>(
+    (a, b, c): Args<W>,
+    r: Registers<W, N>,
+    m: Arithmetic,
+) -> Outcome<W, N> {
+    muli((a, read(&r, b, 1)?, c), r, m)
 }
 
-fn banr((a, b, c): Args, r: Registers) -> Registers {
-    bani((a, r[b], c), r)
+fn bani<W: Word, const N: usize>(
+    (a, b, c): Args<W>,
+    r: Registers<W, N>,
+    _: Arithmetic,
+) -> Outcome<W, N> {
+    let value = read(&r, a, 0)? & b;
+    write(r, c, value)
 }
 
-fn bori((a, b, c): Args, r: Registers) -> Registers {
-    let mut out = r.clone();
-    out[c] = r[a] | b;
-    out
+fn banr<W: Word, const N: usize>(
+    (a, b, c): Args<W>,
+    r: Registers<W, N>,
+    m: Arithmetic,
+) -> Outcome<W, N> {
+    bani((a, read(&r, b, 1)?, c), r, m)
 }
 
-fn borr((a, b, c): Args, r: Registers) -> Registers {
-    bori((a, r[b], c), r)
+fn bori<W: Word, const N: usize>(
+    (a, b, c): Args<W>,
+    r: Registers<W, N>,
+    _: Arithmetic,
+) -> Outcome<W, N> {
+    let value = read(&r, a, 0)? | b;
+    write(r, c, value)
 }
 
-fn seti((a, _, c): Args, r: Registers) -> Registers {
-    let mut out = r.clone();
-    out[c] = a;
-    out
+fn borr<W: Word, const N: usize>(
+    (a, b, c): Args<W>,
+    r: Registers<W, N>,
+    m: Arithmetic,
+) -> Outcome<W, N> {
+    bori((a, read(&r, b, 1)?, c), r, m)
 }
 
-fn setr((a, b, c): Args, r: Registers) -> Registers {
-    seti((r[a], b, c), r)
+fn seti<W: Word, const N: usize>(
+    (a, _, c): Args<W>,
+    r: Registers<W, N>,
+    _: Arithmetic,
+) -> Outcome<W, N> {
+    write(r, c, a)
 }
 
-fn gtir((a, b, c): Args, r: Registers) -> Registers {
-    let mut out = r.clone();
-    out[c] = if a > r[b] { 1 } else { 0 };
-    out
+fn setr<W: Word, const N: usize>(
+    (a, b, c): Args<W>,
+    r: Registers<W, N>,
+    m: Arithmetic,
+) -> Outcome<W, N> {
+    seti((read(&r, a, 0)?, b, c), r, m)
 }
 
-fn gtri((a, b, c): Args, r: Registers) -> Registers {
-    let mut out = r.clone();
-    out[c] = if r[a] > b { 1 } else { 0 };
-    out
+fn gtir<W: Word, const N: usize>(
+    (a, b, c): Args<W>,
+    r: Registers<W, N>,
+    _: Arithmetic,
+) -> Outcome<W, N> {
+    let value = flag(a > read(&r, b, 1)?);
+    write(r, c, value)
 }
 
-fn gtrr((a, b, c): Args, r: Registers) -> Registers {
-    gtri((a, r[b], c), r)
+fn gtri<W: Word, const N: usize>(
+    (a, b, c): Args<W>,
+    r: Registers<W, N>,
+    _: Arithmetic,
+) -> Outcome<W, N> {
+    let value = flag(read(&r, a, 0)? > b);
+    write(r, c, value)
 }
 
-fn eqir((a, b, c): Args, r: Registers) -> Registers {
-    let mut out = r.clone();
-    out[c] = if a == r[b] { 1 } else { 0 };
-    out
+fn gtrr<W: Word, const N: usize>(
+    (a, b, c): Args<W>,
+    r: Registers<W, N>,
+    m: Arithmetic,
+) -> Outcome<W, N> {
+    gtri((a, read(&r, b, 1)?, c), r, m)
 }
 
-fn eqri((a, b, c): Args, r: Registers) -> Registers {
-    let mut out = r.clone();
-    out[c] = if r[a] == b { 1 } else { 0 };
-    out
+fn eqir<W: Word, const N: usize>(
+    (a, b, c): Args<W>,
+    r: Registers<W, N>,
+    _: Arithmetic,
+) -> Outcome<W, N> {
+    let value = flag(a == read(&r, b, 1)?);
+    write(r, c, value)
 }
 
-fn eqrr((a, b, c): Args, r: Registers) -> Registers {
-    eqri((a, r[b], c), r)
+fn eqri<W: Word, const N: usize>(
+    (a, b, c): Args<W>,
+    r: Registers<W, N>,
+    _: Arithmetic,
+) -> Outcome<W, N> {
+    let value = flag(read(&r, a, 0)? == b);
+    write(r, c, value)
+}
+
+fn eqrr<W: Word, const N: usize>(
+    (a, b, c): Args<W>,
+    r: Registers<W, N>,
+    m: Arithmetic,
+) -> Outcome<W, N> {
+    eqri((a, read(&r, b, 1)?, c), r, m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::errors::ErrorKind;
+    use super::{Arithmetic, Fault, Machine, Operation, Registers};
+
+    #[test]
+    fn test_source_round_trip() {
+        for &data in [
+            include_str!("../fixtures/day_19.in"),
+            include_str!("../fixtures/day_21.in"),
+        ]
+        .iter()
+        {
+            let machine: Machine = data.parse().unwrap();
+            assert_eq!(data, machine.to_string());
+            assert_eq!(machine, machine.to_string().parse().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_modes() {
+        let mut machine: Machine = "#ip 5\nmuli 0 3 0\naddi 0 5 0".parse().unwrap();
+        machine.registers[0] = usize::MAX / 2;
+        let fault = match *machine.step().unwrap_err().kind() {
+            ErrorKind::Fault(line, ref text, fault) => (line, text.clone(), fault),
+            ref other => panic!("unexpected error {}", other),
+        };
+        assert_eq!((0, "muli 0 3 0".to_owned(), Fault::Overflow), fault);
+        assert_eq!(usize::MAX / 2, machine.registers[0]);
+
+        machine.arithmetic = Arithmetic::Saturating;
+        while machine.next().unwrap() {}
+        assert_eq!(usize::MAX, machine.registers[0]);
+
+        machine.arithmetic = Arithmetic::Wrapping;
+        machine.registers = [usize::MAX / 2, 0, 0, 0, 0, 0];
+        while machine.next().unwrap() {}
+        assert_eq!((usize::MAX / 2).wrapping_mul(3) + 5, machine.registers[0]);
+    }
+
+    #[test]
+    fn test_invalid_register() {
+        let addr = Operation::OPS
+            .iter()
+            .find(|op| op.name() == "addr")
+            .unwrap();
+        let registers: Registers<i64, 4> = [1, 2, 3, 4];
+        let run = |args| addr.execute(args, registers, Arithmetic::Checked);
+        assert_eq!(Ok([1, 2, 3, 3]), run((0, 1, 3)));
+        assert_eq!(Err(Fault::InvalidRegister(0)), run((-1, 1, 3)));
+        assert_eq!(Err(Fault::InvalidRegister(1)), run((0, 4, 3)));
+        assert_eq!(Err(Fault::InvalidRegister(2)), run((0, 1, 4)));
+    }
 }
@@ -0,0 +1,345 @@
+//! Assembler for elfcode source.
+//!
+//! Besides the bare `#ip N` header and `op a b c` lines of the puzzle input,
+//! the assembler understands
+//!
+//! - `;` comments running to the end of the line, and blank lines,
+//! - `label:` definitions, which evaluate to the index of the next instruction,
+//! - `.reg name = N` aliases for register numbers,
+//! - `symbol+N` and `symbol-N` offsets on symbolic arguments.
+//!
+//! Symbols may be used before they are defined, including in `#ip`. Keep in
+//! mind that the instruction pointer is incremented after every instruction,
+//! so jumping to `loop` is written `seti loop-1 0 ip`.
+
+use std::collections::HashMap;
+
+use super::errors::*;
+use super::{parse_operation, Args, Machine, Operand, Operation, REGISTER_COUNT};
+
+#[derive(Debug, Clone, Copy)]
+struct Pos {
+    line: usize,
+    col: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    text: &'a str,
+    pos: Pos,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Value<'a> {
+    Number(usize),
+    Symbol(&'a str, isize),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Arg<'a> {
+    value: Value<'a>,
+    pos: Pos,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Symbol {
+    value: usize,
+    pos: Pos,
+}
+
+pub fn assemble(data: &str) -> Result<Machine> {
+    let mut symbols: HashMap<&str, Symbol> = HashMap::new();
+    let mut ip: Option<Arg> = None;
+    let mut pending: Vec<(Operation, [Arg; 3])> = Vec::new();
+    for (index, source_line) in data.lines().enumerate() {
+        let tokens = tokenize(source_line, index + 1);
+        let mut tokens = tokens.iter().cloned().peekable();
+        while let Some(label) = tokens.peek().cloned().filter(|t| t.text.ends_with(':')) {
+            tokens.next();
+            let name = Token {
+                text: &label.text[..label.text.len() - 1],
+                pos: label.pos,
+            };
+            define(&mut symbols, name, pending.len())?;
+        }
+        let head = match tokens.next() {
+            None => continue,
+            Some(head) => head,
+        };
+        let end = Pos {
+            line: index + 1,
+            col: source_line.chars().count() + 1,
+        };
+        match head.text {
+            "#ip" => {
+                if ip.is_some() {
+                    return syntax(head.pos, "duplicate #ip directive".to_owned());
+                }
+                let arg = tokens
+                    .next()
+                    .ok_or_else(|| syntax_error(end, "expected register after #ip".to_owned()))?;
+                ip = Some(parse_arg(arg)?);
+            }
+            ".reg" => {
+                let name = tokens
+                    .next()
+                    .ok_or_else(|| syntax_error(end, "expected register alias".to_owned()))?;
+                match tokens.next() {
+                    Some(Token { text: "=", .. }) => (),
+                    Some(other) => {
+                        return syntax(other.pos, format!("expected '=', found '{}'", other.text))
+                    }
+                    None => return syntax(end, "expected '='".to_owned()),
+                }
+                let register = tokens
+                    .next()
+                    .ok_or_else(|| syntax_error(end, "expected register number".to_owned()))?;
+                let value = match parse_arg(register)?.value {
+                    Value::Number(value) if value < REGISTER_COUNT => value,
+                    _ => return syntax(register.pos, out_of_range(register.text)),
+                };
+                define(&mut symbols, name, value)?;
+            }
+            name => {
+                let op = parse_operation(name)
+                    .map_err(|_| syntax_error(head.pos, format!("unknown operation '{}'", name)))?;
+                let mut args = Vec::with_capacity(3);
+                for _ in 0..3 {
+                    let arg = tokens.next().ok_or_else(|| {
+                        syntax_error(
+                            end,
+                            format!("'{}' expects 3 arguments, found {}", name, args.len()),
+                        )
+                    })?;
+                    args.push(parse_arg(arg)?);
+                }
+                pending.push((op, [args[0], args[1], args[2]]));
+            }
+        }
+        if let Some(extra) = tokens.next() {
+            return syntax(extra.pos, format!("unexpected '{}'", extra.text));
+        }
+    }
+
+    let mut machine = Machine::default();
+    let ip = ip
+        .ok_or_else(|| syntax_error(Pos { line: 1, col: 1 }, "missing #ip directive".to_owned()))?;
+    machine.instruction_pointer = resolve(&symbols, ip, Operand::Register)?;
+    for (op, args) in pending {
+        let kinds = op.operands();
+        let args: Args = (
+            resolve(&symbols, args[0], kinds[0])?,
+            resolve(&symbols, args[1], kinds[1])?,
+            resolve(&symbols, args[2], kinds[2])?,
+        );
+        machine.instructions.push((op, args));
+    }
+    Ok(machine)
+}
+
+fn tokenize(line: &str, line_number: usize) -> Vec<Token<'_>> {
+    let code = line.split(';').next().unwrap_or("");
+    let mut tokens = Vec::new();
+    let mut start: Option<(usize, usize)> = None;
+    for (col, (offset, c)) in code.char_indices().enumerate() {
+        let separator = c.is_whitespace() || c == ',' || c == '=';
+        if separator {
+            if let Some((start_offset, start_col)) = start.take() {
+                tokens.push(Token {
+                    text: &code[start_offset..offset],
+                    pos: Pos {
+                        line: line_number,
+                        col: start_col + 1,
+                    },
+                });
+            }
+            if c == '=' {
+                tokens.push(Token {
+                    text: &code[offset..offset + 1],
+                    pos: Pos {
+                        line: line_number,
+                        col: col + 1,
+                    },
+                });
+            }
+        } else if start.is_none() {
+            start = Some((offset, col));
+        }
+    }
+    if let Some((start_offset, start_col)) = start {
+        tokens.push(Token {
+            text: &code[start_offset..],
+            pos: Pos {
+                line: line_number,
+                col: start_col + 1,
+            },
+        });
+    }
+    tokens
+}
+
+fn parse_arg(token: Token) -> Result<Arg> {
+    let text = token.text;
+    if text.starts_with(|c: char| c.is_ascii_digit()) {
+        let value = text
+            .parse()
+            .map_err(|_| syntax_error(token.pos, format!("invalid number '{}'", text)))?;
+        return Ok(Arg {
+            value: Value::Number(value),
+            pos: token.pos,
+        });
+    }
+    let split = text.find(&['+', '-'][..]).unwrap_or(text.len());
+    let (name, offset) = text.split_at(split);
+    if !is_identifier(name) {
+        return syntax(token.pos, format!("invalid argument '{}'", text));
+    }
+    let offset: isize = match offset {
+        "" => 0,
+        _ => {
+            let magnitude: isize = offset[1..]
+                .parse()
+                .map_err(|_| syntax_error(token.pos, format!("invalid offset in '{}'", text)))?;
+            if offset.starts_with('-') {
+                -magnitude
+            } else {
+                magnitude
+            }
+        }
+    };
+    Ok(Arg {
+        value: Value::Symbol(name, offset),
+        pos: token.pos,
+    })
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_alphabetic() || c == '_' => chars.all(|c| c.is_alphanumeric() || c == '_'),
+        _ => false,
+    }
+}
+
+fn define<'a>(symbols: &mut HashMap<&'a str, Symbol>, name: Token<'a>, value: usize) -> Result<()> {
+    if !is_identifier(name.text) {
+        return syntax(name.pos, format!("invalid symbol name '{}'", name.text));
+    }
+    if let Some(previous) = symbols.get(name.text) {
+        return syntax(
+            name.pos,
+            format!(
+                "symbol '{}' already defined at {}:{}",
+                name.text, previous.pos.line, previous.pos.col
+            ),
+        );
+    }
+    symbols.insert(
+        name.text,
+        Symbol {
+            value,
+            pos: name.pos,
+        },
+    );
+    Ok(())
+}
+
+fn resolve(symbols: &HashMap<&str, Symbol>, arg: Arg, kind: Operand) -> Result<usize> {
+    let value = match arg.value {
+        Value::Number(value) => value,
+        Value::Symbol(name, offset) => {
+            let symbol = symbols
+                .get(name)
+                .ok_or_else(|| syntax_error(arg.pos, format!("undefined symbol '{}'", name)))?;
+            let value = symbol.value as isize + offset;
+            if value < 0 {
+                return syntax(arg.pos, format!("'{}' evaluates to {}", name, value));
+            }
+            value as usize
+        }
+    };
+    if kind == Operand::Register && value >= REGISTER_COUNT {
+        return syntax(arg.pos, out_of_range(&value.to_string()));
+    }
+    Ok(value)
+}
+
+fn out_of_range(register: &str) -> String {
+    format!(
+        "register {} out of range, expected 0 to {}",
+        register,
+        REGISTER_COUNT - 1
+    )
+}
+
+fn syntax_error(pos: Pos, msg: String) -> Error {
+    ErrorKind::Syntax(pos.line, pos.col, msg).into()
+}
+
+fn syntax<T>(pos: Pos, msg: String) -> Result<T> {
+    Err(syntax_error(pos, msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::assemble;
+
+    #[test]
+    fn test_assemble_symbols() {
+        let mut machine = assemble(
+            r"; counts r[0] up to 3
+.reg ip = 5
+.reg n = 0
+#ip ip
+
+    seti 0 0 n
+loop:
+    addi n 1 n      ; n += 1
+    gtri n 2 4
+    addr 4 ip ip
+    seti loop-1 0 ip
+done: seti done 0 ip
+",
+        )
+        .unwrap();
+        assert_eq!(5, machine.instruction_pointer);
+        let args: Vec<_> = machine.instructions.iter().map(|&(_, args)| args).collect();
+        assert_eq!(
+            vec![
+                (0, 0, 0),
+                (0, 1, 0),
+                (0, 2, 4),
+                (4, 5, 5),
+                (0, 0, 5),
+                (5, 0, 5)
+            ],
+            args
+        );
+        while machine.next().unwrap() {}
+        assert_eq!(3, machine.registers[0]);
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        let error = |data: &str| assemble(data).unwrap_err().to_string();
+        assert_eq!("2:1: unknown operation 'adi'", error("#ip 0\nadi 1 2 3"));
+        assert_eq!("2:12: unexpected 'loop'", error("#ip 0\nseti 0 0 0 loop"));
+        assert_eq!(
+            "3:6: undefined symbol 'loop'",
+            error("#ip 0\n\nseti loop 0 0")
+        );
+        assert_eq!(
+            "2:10: register 6 out of range, expected 0 to 5",
+            error("#ip 0\naddi 1 2 6")
+        );
+        assert_eq!(
+            "2:9: 'addi' expects 3 arguments, found 2",
+            error("#ip 0\naddi 1 2")
+        );
+        assert_eq!(
+            "3:1: symbol 'a' already defined at 2:1",
+            error("#ip 0\na: seti 0 0 0\na: seti 0 0 0")
+        );
+        assert_eq!("1:1: missing #ip directive", error("seti 0 0 0"));
+    }
+}
@@ -0,0 +1,231 @@
+//! Breakpoints, watchpoints and tracing on top of `Machine::step`.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use super::errors::*;
+use super::{Instruction, Machine, Registers};
+
+/// A single executed instruction together with the registers around it
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Step {
+    pub line: usize,
+    pub instruction: Instruction,
+    pub before: Registers,
+    pub after: Registers,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (op, (a, b, c)) = self.instruction;
+        write!(
+            f,
+            "{}: {} {} {} {}  {:?} -> {:?}",
+            self.line,
+            op.name(),
+            a,
+            b,
+            c,
+            self.before,
+            self.after
+        )
+    }
+}
+
+/// Why execution stopped
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Stop {
+    /// The instruction pointer left the program
+    Halted,
+    /// The next instruction is on a breakpoint line
+    Breakpoint(usize),
+    /// The step starting on `line` changed a watched register
+    Watchpoint {
+        line: usize,
+        register: usize,
+        old: usize,
+        new: usize,
+    },
+    /// The predicate given to `Machine::run_until` holds
+    Predicate,
+    /// The instruction budget ran out
+    Budget,
+}
+
+impl fmt::Display for Stop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Stop::Halted => write!(f, "halted"),
+            Stop::Breakpoint(line) => write!(f, "breakpoint at {}", line),
+            Stop::Watchpoint {
+                line,
+                register,
+                old,
+                new,
+            } => write!(
+                f,
+                "{}: r[{}] changed from {} to {}",
+                line, register, old, new
+            ),
+            Stop::Predicate => write!(f, "predicate reached"),
+            Stop::Budget => write!(f, "instruction budget exhausted"),
+        }
+    }
+}
+
+type Trace<'a> = Box<dyn FnMut(&Step) + 'a>;
+
+pub struct Debugger<'a> {
+    pub machine: Machine,
+    /// Lines to stop before. A loop that `Machine::optimize` replaced runs
+    /// as one step, so breakpoints on its lines after the first are skipped.
+    pub breakpoints: BTreeSet<usize>,
+    pub watchpoints: BTreeSet<usize>,
+    /// Number of instructions executed so far
+    pub executed: usize,
+    trace: Option<Trace<'a>>,
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(machine: Machine) -> Debugger<'a> {
+        Debugger {
+            machine,
+            breakpoints: BTreeSet::new(),
+            watchpoints: BTreeSet::new(),
+            executed: 0,
+            trace: None,
+        }
+    }
+
+    /// Calls `trace` with every instruction executed from now on
+    pub fn set_trace<F>(&mut self, trace: F)
+    where
+        F: FnMut(&Step) + 'a,
+    {
+        self.trace = Some(Box::new(trace));
+    }
+
+    pub fn clear_trace(&mut self) {
+        self.trace = None;
+    }
+
+    pub fn is_tracing(&self) -> bool {
+        self.trace.is_some()
+    }
+
+    /// Executes a single instruction, or a whole optimized loop, ignoring
+    /// breakpoints. Returns the watchpoint it triggered, if any: the lowest
+    /// watched register whose value changed.
+    pub fn step(&mut self) -> Result<Option<Stop>> {
+        let step = match self.machine.step()? {
+            None => return Ok(Some(Stop::Halted)),
+            Some(step) => step,
+        };
+        self.executed += 1;
+        if let Some(ref mut trace) = self.trace {
+            trace(&step);
+        }
+        let changed = self
+            .watchpoints
+            .iter()
+            .cloned()
+            .find(|&r| r < step.after.len() && step.before[r] != step.after[r]);
+        Ok(changed.map(|register| Stop::Watchpoint {
+            line: step.line,
+            register,
+            old: step.before[register],
+            new: step.after[register],
+        }))
+    }
+
+    /// Runs until a breakpoint or watchpoint triggers, the machine halts or
+    /// `budget` instructions have been executed. A breakpoint on the current
+    /// line does not stop the first instruction, so that continuing from a
+    /// breakpoint makes progress.
+    pub fn run(&mut self, budget: usize) -> Result<Stop> {
+        for executed in 0..budget {
+            let line = self.machine.line();
+            if executed > 0 && self.breakpoints.contains(&line) {
+                return Ok(Stop::Breakpoint(line));
+            }
+            if let Some(stop) = self.step()? {
+                return Ok(stop);
+            }
+        }
+        Ok(Stop::Budget)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Machine;
+    use super::{Debugger, Stop};
+    use std::cell::RefCell;
+
+    const DATA: &str = r"#ip 0
+seti 5 0 1
+seti 6 0 2
+addi 0 1 0
+addr 1 2 3
+setr 1 0 0
+seti 8 0 4
+seti 9 0 5";
+
+    #[test]
+    fn test_breakpoints_and_watchpoints() {
+        let machine: Machine = DATA.parse().unwrap();
+        let lines = RefCell::new(Vec::new());
+        let mut debugger = Debugger::new(machine);
+        debugger.set_trace(|step| lines.borrow_mut().push(step.line));
+        debugger.breakpoints.insert(4);
+        debugger.watchpoints.insert(5);
+        assert_eq!(Stop::Breakpoint(4), debugger.run(100).unwrap());
+        assert_eq!(4, debugger.machine.line());
+        assert_eq!(
+            Stop::Watchpoint {
+                line: 6,
+                register: 5,
+                old: 0,
+                new: 9,
+            },
+            debugger.run(100).unwrap()
+        );
+        assert_eq!(Stop::Halted, debugger.run(100).unwrap());
+        assert_eq!(5, debugger.executed);
+        drop(debugger);
+        assert_eq!(vec![0, 1, 2, 4, 6], lines.into_inner());
+    }
+
+    #[test]
+    fn test_watchpoint_in_optimized_loop() {
+        let mut machine: Machine = include_str!("../../fixtures/day_19.in").parse().unwrap();
+        machine.optimize();
+        let mut debugger = Debugger::new(machine);
+        // r[5] is the inner counter of the divisor sum loop on lines 1 to 15
+        debugger.watchpoints.insert(5);
+        debugger.breakpoints.insert(8);
+        match debugger.run(1000).unwrap() {
+            Stop::Watchpoint {
+                line: 1,
+                register: 5,
+                old: 0,
+                new,
+            } => assert!(new > 1),
+            stop => panic!("unexpected stop: {}", stop),
+        }
+        assert_eq!(16, debugger.machine.line());
+    }
+
+    #[test]
+    fn test_run_until() {
+        let mut machine: Machine = DATA.parse().unwrap();
+        assert_eq!(Stop::Budget, machine.run_until(2, |_| false).unwrap());
+        assert_eq!(2, machine.line());
+        assert_eq!(
+            Stop::Predicate,
+            machine.run_until(10, |m| m.line() == 4).unwrap()
+        );
+        assert_eq!(Stop::Halted, machine.run_until(10, |_| false).unwrap());
+        assert_eq!(7, machine.registers[0]);
+    }
+}
@@ -0,0 +1,402 @@
+//! Structured decompilation of elfcode programs.
+//!
+//! Every write to the instruction pointer register is lowered to a jump. Jumps
+//! whose target only depends on the instruction pointer become plain `goto`s,
+//! and `addr` of a fresh comparison result becomes a conditional branch. Loops
+//! are then recovered from backward jumps and `if`/`else` blocks from forward
+//! branches, while anything that does not fit is left as a numbered `goto`.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use super::{deparse_instruction, Arithmetic, Machine, Operand};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cond {
+    lhs: String,
+    op: &'static str,
+    rhs: String,
+}
+
+impl Cond {
+    fn negate(&self) -> Cond {
+        let op = match self.op {
+            "==" => "!=",
+            "!=" => "==",
+            ">" => "<=",
+            _ => ">",
+        };
+        Cond {
+            lhs: self.lhs.clone(),
+            op,
+            rhs: self.rhs.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Cond {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.lhs, self.op, self.rhs)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Stmt {
+    Assign(String),
+    Goto(usize),
+    Branch(Cond, usize),
+    /// `goto line + 1 + r[register]`
+    Offset(usize),
+    Indirect(String),
+    /// A `goto` that was merged into the branch on the line above
+    Folded,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Loop {
+    header: usize,
+    exit: usize,
+}
+
+struct Decompiler {
+    stmts: Vec<Stmt>,
+    out: String,
+}
+
+impl Machine {
+    /// Like `deparse`, but with jumps resolved to absolute lines and nested
+    /// `loop`, `do`/`while` and `if`/`else` blocks where the control flow allows.
+    pub fn decompile(&self) -> String {
+        let mut decompiler = Decompiler {
+            stmts: lower(self),
+            out: String::new(),
+        };
+        let len = decompiler.stmts.len();
+        decompiler.emit_range(0, len, 0, None);
+        decompiler.out
+    }
+}
+
+fn lower(machine: &Machine) -> Vec<Stmt> {
+    let ip = machine.instruction_pointer;
+    let mut stmts = Vec::with_capacity(machine.instructions.len());
+    for (line, &instruction) in machine.instructions.iter().enumerate() {
+        let (op, (a, b, c)) = instruction;
+        let reads_ip_only = op
+            .operands()
+            .iter()
+            .zip([a, b].iter())
+            .all(|(&kind, &arg)| kind != Operand::Register || arg == ip);
+        let stmt = if c != ip {
+            Stmt::Assign(deparse_instruction(line, ip, instruction))
+        } else if reads_ip_only {
+            let mut registers = [0; super::REGISTER_COUNT];
+            registers[ip] = line;
+            match op.execute((a, b, c), registers, Arithmetic::Wrapping) {
+                Ok(registers) => Stmt::Goto(registers[ip].wrapping_add(1)),
+                Err(_) => Stmt::Indirect(deparse_instruction(line, ip, instruction)),
+            }
+        } else if op.name() == "addr" && (a == ip || b == ip) {
+            Stmt::Offset(if a == ip { b } else { a })
+        } else {
+            Stmt::Indirect(deparse_instruction(line, ip, instruction))
+        };
+        stmts.push(stmt);
+    }
+
+    // `addr` of a register which was just set by a comparison skips exactly
+    // one instruction if the comparison held
+    let targets = jump_targets(&stmts, true);
+    let offsets: Vec<_> = stmts
+        .iter()
+        .enumerate()
+        .filter_map(|(line, stmt)| match *stmt {
+            Stmt::Offset(register) if line > 0 && !targets.contains(&line) => {
+                Some((line, register))
+            }
+            _ => None,
+        })
+        .collect();
+    for (line, register) in offsets {
+        if let Some(cond) = comparison(machine, line - 1, register) {
+            stmts[line] = Stmt::Branch(cond, line + 2);
+        }
+    }
+
+    // `if (c) goto line + 2; goto t;` is `if (!c) goto t;`
+    let targets = jump_targets(&stmts, false);
+    for line in 0..stmts.len().max(1) - 1 {
+        let folded = match (&stmts[line], &stmts[line + 1]) {
+            (&Stmt::Branch(ref cond, target), &Stmt::Goto(next))
+                if target == line + 2 && !targets.contains(&(line + 1)) =>
+            {
+                Some(Stmt::Branch(cond.negate(), next))
+            }
+            _ => None,
+        };
+        if let Some(stmt) = folded {
+            stmts[line] = stmt;
+            stmts[line + 1] = Stmt::Folded;
+        }
+    }
+    stmts
+}
+
+/// Lines that may be reached other than by falling through. An indirect jump
+/// may land on any line after it, unless `offsets_are_branches` assumes that
+/// every offset jump skips at most one line.
+fn jump_targets(stmts: &[Stmt], offsets_are_branches: bool) -> HashSet<usize> {
+    let mut targets = HashSet::new();
+    let mut indirect = None;
+    for (line, stmt) in stmts.iter().enumerate() {
+        match *stmt {
+            Stmt::Goto(target) | Stmt::Branch(_, target) => {
+                targets.insert(target);
+            }
+            Stmt::Offset(_) if offsets_are_branches => {
+                targets.insert(line + 2);
+            }
+            Stmt::Offset(_) | Stmt::Indirect(_) => {
+                indirect = indirect.or(Some(line));
+            }
+            _ => (),
+        };
+    }
+    if let Some(first) = indirect {
+        targets.extend(first + 1..stmts.len());
+    }
+    targets
+}
+
+/// The condition tested by `register` if `line` is a comparison writing it
+fn comparison(machine: &Machine, line: usize, register: usize) -> Option<Cond> {
+    let ip = machine.instruction_pointer;
+    let (op, (a, b, c)) = machine.instructions[line];
+    let cmp = match &op.name()[..2] {
+        "gt" => ">",
+        "eq" => "==",
+        _ => return None,
+    };
+    if c != register {
+        return None;
+    }
+    let kinds = op.operands();
+    let clobbered =
+        (kinds[0] == Operand::Register && a == c) || (kinds[1] == Operand::Register && b == c);
+    if clobbered {
+        return Some(Cond {
+            lhs: format!("r[{}]", c),
+            op: "!=",
+            rhs: "0".to_owned(),
+        });
+    }
+    let operand = |kind, arg: usize| match kind {
+        Operand::Register if arg == ip => line.to_string(),
+        Operand::Register => format!("r[{}]", arg),
+        _ => arg.to_string(),
+    };
+    Some(Cond {
+        lhs: operand(kinds[0], a),
+        op: cmp,
+        rhs: operand(kinds[1], b),
+    })
+}
+
+impl Decompiler {
+    fn numbered(&mut self, line: usize, depth: usize, text: &str) {
+        let indent = "    ".repeat(depth);
+        self.out += &format!("{}{}: {}\n", indent, line, text);
+    }
+
+    fn bare(&mut self, depth: usize, text: &str) {
+        let indent = "    ".repeat(depth);
+        self.out += &format!("{}{}\n", indent, text);
+    }
+
+    fn jumps_to(&self, line: usize, target: usize) -> bool {
+        match self.stmts[line] {
+            Stmt::Goto(t) | Stmt::Branch(_, t) => t == target,
+            _ => false,
+        }
+    }
+
+    fn jump(&self, target: usize, current: Option<Loop>) -> String {
+        match current {
+            _ if target >= self.stmts.len() => "halt;".to_owned(),
+            Some(l) if target == l.header => "continue;".to_owned(),
+            Some(l) if target == l.exit => "break;".to_owned(),
+            _ => format!("goto {};", target),
+        }
+    }
+
+    fn emit_range(&mut self, lo: usize, hi: usize, depth: usize, current: Option<Loop>) {
+        let mut line = lo;
+        while line < hi {
+            let is_header = current.map_or(false, |l| l.header == line);
+            let back_edge = (line..hi).rev().find(|&j| self.jumps_to(j, line));
+            if let (false, Some(last)) = (is_header, back_edge) {
+                let exit = (last + 1..self.stmts.len())
+                    .find(|&j| self.stmts[j] != Stmt::Folded)
+                    .unwrap_or(self.stmts.len());
+                let inner = Some(Loop { header: line, exit });
+                match self.stmts[last].clone() {
+                    Stmt::Branch(cond, _) => {
+                        self.bare(depth, "do {");
+                        self.emit_range(line, last, depth + 1, inner);
+                        self.numbered(last, depth, &format!("}} while ({});", cond));
+                    }
+                    _ => {
+                        self.bare(depth, "loop {");
+                        self.emit_range(line, last, depth + 1, inner);
+                        self.numbered(last, depth, "}");
+                    }
+                }
+                line = last + 1;
+                continue;
+            }
+
+            match self.stmts[line].clone() {
+                Stmt::Assign(text) | Stmt::Indirect(text) => self.numbered(line, depth, &text),
+                Stmt::Offset(register) => {
+                    let text = format!("goto {} + r[{}];", line + 1, register);
+                    self.numbered(line, depth, &text);
+                }
+                Stmt::Folded => (),
+                Stmt::Goto(target) => {
+                    let text = self.jump(target, current);
+                    self.numbered(line, depth, &text);
+                }
+                Stmt::Branch(cond, target) => {
+                    let structured = target > line + 1
+                        && target <= hi
+                        && target < self.stmts.len()
+                        && current.map_or(true, |l| target != l.header && target != l.exit);
+                    if !structured {
+                        let text = format!("if ({}) {}", cond, self.jump(target, current));
+                        self.numbered(line, depth, &text);
+                        line += 1;
+                        continue;
+                    }
+                    self.numbered(line, depth, &format!("if ({}) {{", cond.negate()));
+                    let otherwise = match self.stmts[target - 1] {
+                        Stmt::Goto(end)
+                            if target - 1 > line
+                                && end > target
+                                && end <= hi
+                                && current.map_or(true, |l| end != l.exit) =>
+                        {
+                            Some(end)
+                        }
+                        _ => None,
+                    };
+                    if let Some(end) = otherwise {
+                        self.emit_range(line + 1, target - 1, depth + 1, current);
+                        self.numbered(target - 1, depth, "} else {");
+                        self.emit_range(target, end, depth + 1, current);
+                        self.bare(depth, "}");
+                        line = end;
+                    } else {
+                        self.emit_range(line + 1, target, depth + 1, current);
+                        self.bare(depth, "}");
+                        line = target;
+                    }
+                    continue;
+                }
+            }
+            line += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Machine;
+
+    #[test]
+    fn test_decompile_nested_loops() {
+        let machine: Machine = include_str!("../../fixtures/day_21.in").parse().unwrap();
+        assert_eq!(
+            r"0: r[3] = 123;
+do {
+    1: r[3] = r[3] & 456;
+    2: r[3] = (r[3] == 72);
+3: } while (r[3] == 0);
+5: r[3] = 0;
+do {
+    6: r[1] = r[3] | 65536;
+    7: r[3] = 10373714;
+    loop {
+        8: r[5] = r[1] & 255;
+        9: r[3] = r[3] + r[5];
+        10: r[3] = r[3] & 16777215;
+        11: r[3] = r[3] * 65899;
+        12: r[3] = r[3] & 16777215;
+        13: r[5] = (256 > r[1]);
+        14: if (256 > r[1]) {
+            16: break;
+        }
+        17: r[5] = 0;
+        loop {
+            18: r[4] = r[5] + 1;
+            19: r[4] = r[4] * 256;
+            20: r[4] = (r[4] > r[1]);
+            21: if (r[4] != 0) {
+                23: break;
+            }
+            24: r[5] = r[5] + 1;
+        25: }
+        26: r[1] = r[5];
+    27: }
+    28: r[5] = (r[3] == r[0]);
+29: } while (r[3] != r[0]);
+",
+            machine.decompile()
+        );
+    }
+
+    #[test]
+    fn test_decompile_divisor_sum() {
+        let machine: Machine = include_str!("../../fixtures/day_19.in").parse().unwrap();
+        assert_eq!(
+            r"0: goto 17;
+loop {
+    1: r[3] = 1;
+    do {
+        2: r[5] = 1;
+        do {
+            3: r[1] = r[3] * r[5];
+            4: r[1] = (r[1] == r[2]);
+            5: if (r[1] != 0) {
+                7: r[0] = r[3] + r[0];
+            }
+            8: r[5] = r[5] + 1;
+            9: r[1] = (r[5] > r[2]);
+        10: } while (r[5] <= r[2]);
+        12: r[3] = r[3] + 1;
+        13: r[1] = (r[3] > r[2]);
+    14: } while (r[3] <= r[2]);
+    16: halt;
+    17: r[2] = r[2] + 2;
+    18: r[2] = r[2] * r[2];
+    19: r[2] = 19 * r[2];
+    20: r[2] = r[2] * 11;
+    21: r[1] = r[1] + 2;
+    22: r[1] = r[1] * 22;
+    23: r[1] = r[1] + 7;
+    24: r[2] = r[2] + r[1];
+    25: goto 26 + r[0];
+    26: continue;
+    27: r[1] = 27;
+    28: r[1] = r[1] * 28;
+    29: r[1] = 29 + r[1];
+    30: r[1] = 30 * r[1];
+    31: r[1] = r[1] * 14;
+    32: r[1] = r[1] * 32;
+    33: r[2] = r[2] + r[1];
+    34: r[0] = 0;
+35: }
+",
+            machine.decompile()
+        );
+    }
+}
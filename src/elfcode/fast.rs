@@ -0,0 +1,182 @@
+//! An execution engine for long runs.
+//!
+//! `Machine::step` looks up an `Operation`, calls it through a function
+//! pointer and copies the registers in and out, which is fine for debugging
+//! but slow for programs that run for billions of instructions. `Interpreter`
+//! decodes the program once into a compact enum with validated register
+//! indices, and then executes it with a single `match` per instruction on
+//! registers that are updated in place.
+
+use super::errors::*;
+use super::{Fault, Machine, Operand, Registers, Stop};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+enum Op {
+    Addi,
+    Addr,
+    Muli,
+    Mulr,
+    Bani,
+    Banr,
+    Bori,
+    Borr,
+    Seti,
+    Setr,
+    Gtir,
+    Gtri,
+    Gtrr,
+    Eqir,
+    Eqri,
+    Eqrr,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+struct Decoded {
+    op: Op,
+    a: usize,
+    b: usize,
+    c: usize,
+}
+
+/// Executes a decoded copy of a machine's program. Arithmetic always wraps,
+/// and natively executed idioms are not used.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Interpreter {
+    instruction_pointer: usize,
+    code: Vec<Decoded>,
+    pub registers: Registers,
+}
+
+impl Interpreter {
+    /// Decodes the program of `machine`, starting from its current registers.
+    /// Fails on the first instruction with a register argument out of range.
+    pub fn new(machine: &Machine) -> Result<Interpreter> {
+        let mut code = Vec::with_capacity(machine.instructions.len());
+        for (line, &(op, (a, b, c))) in machine.instructions.iter().enumerate() {
+            let args = [a, b, c];
+            let invalid = op
+                .operands()
+                .iter()
+                .zip(args.iter())
+                .position(|(&kind, &arg)| {
+                    kind == Operand::Register && arg >= machine.registers.len()
+                });
+            if let Some(position) = invalid {
+                let text = format!("{} {} {} {}", op.name(), a, b, c);
+                bail!(ErrorKind::Fault(
+                    line,
+                    text,
+                    Fault::InvalidRegister(position)
+                ));
+            }
+            let op = match op.name() {
+                "addi" => Op::Addi,
+                "addr" => Op::Addr,
+                "muli" => Op::Muli,
+                "mulr" => Op::Mulr,
+                "bani" => Op::Bani,
+                "banr" => Op::Banr,
+                "bori" => Op::Bori,
+                "borr" => Op::Borr,
+                "seti" => Op::Seti,
+                "setr" => Op::Setr,
+                "gtir" => Op::Gtir,
+                "gtri" => Op::Gtri,
+                "gtrr" => Op::Gtrr,
+                "eqir" => Op::Eqir,
+                "eqri" => Op::Eqri,
+                _ => Op::Eqrr,
+            };
+            code.push(Decoded { op, a, b, c });
+        }
+        Ok(Interpreter {
+            instruction_pointer: machine.instruction_pointer,
+            code,
+            registers: machine.registers,
+        })
+    }
+
+    /// The index of the instruction that executes next
+    pub fn line(&self) -> usize {
+        self.registers[self.instruction_pointer]
+    }
+
+    /// Runs until the machine halts or `budget` instructions have been
+    /// executed
+    pub fn run(&mut self, budget: usize) -> Stop {
+        self.run_until_line(usize::MAX, budget)
+    }
+
+    /// Like `run`, but also stops with `Stop::Predicate` when the instruction
+    /// pointer reaches `line`. The instruction on the current line always
+    /// executes, so that calling this repeatedly makes progress.
+    pub fn run_until_line(&mut self, line: usize, budget: usize) -> Stop {
+        let ip = self.instruction_pointer;
+        let r = &mut self.registers;
+        for executed in 0..budget {
+            let current = r[ip];
+            if executed > 0 && current == line {
+                return Stop::Predicate;
+            }
+            let Decoded { op, a, b, c } = match self.code.get(current) {
+                None => return Stop::Halted,
+                Some(&decoded) => decoded,
+            };
+            r[c] = match op {
+                Op::Addi => r[a].wrapping_add(b),
+                Op::Addr => r[a].wrapping_add(r[b]),
+                Op::Muli => r[a].wrapping_mul(b),
+                Op::Mulr => r[a].wrapping_mul(r[b]),
+                Op::Bani => r[a] & b,
+                Op::Banr => r[a] & r[b],
+                Op::Bori => r[a] | b,
+                Op::Borr => r[a] | r[b],
+                Op::Seti => a,
+                Op::Setr => r[a],
+                Op::Gtir => (a > r[b]) as usize,
+                Op::Gtri => (r[a] > b) as usize,
+                Op::Gtrr => (r[a] > r[b]) as usize,
+                Op::Eqir => (a == r[b]) as usize,
+                Op::Eqri => (r[a] == b) as usize,
+                Op::Eqrr => (r[a] == r[b]) as usize,
+            };
+            r[ip] = r[ip].wrapping_add(1);
+        }
+        if self.code.get(r[ip]).is_none() {
+            Stop::Halted
+        } else {
+            Stop::Budget
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Arithmetic, Machine, Stop};
+    use super::Interpreter;
+
+    #[test]
+    fn test_matches_machine() {
+        let mut machine: Machine = include_str!("../../fixtures/day_21.in").parse().unwrap();
+        machine.arithmetic = Arithmetic::Wrapping;
+        let mut fast = Interpreter::new(&machine).unwrap();
+        for _ in 0..3 {
+            assert_eq!(Stop::Predicate, fast.run_until_line(28, 1_000_000));
+            machine.next().unwrap();
+            machine.run_until(1_000_000, |m| m.line() == 28).unwrap();
+            assert_eq!(machine.registers, fast.registers);
+        }
+        assert_eq!(Stop::Budget, fast.run(1000));
+    }
+
+    #[test]
+    fn test_invalid_register() {
+        let mut machine: Machine = "#ip 0\nseti 0 0 1\naddr 1 2 3".parse().unwrap();
+        machine.instructions[1].1 = (1, 7, 3);
+        let error = Interpreter::new(&machine).unwrap_err();
+        assert_eq!(
+            "1: addr 1 7 3: argument b is not a register",
+            error.to_string()
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! Working out which numeric opcode is which operation from observed samples.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::errors::*;
+use super::{Args, Arithmetic, Operation, Registers, Word, REGISTER_COUNT};
+
+/// The registers before and after executing a single instruction, whose
+/// operation is only known by its numeric opcode
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Sample<W = usize, const N: usize = REGISTER_COUNT> {
+    pub before: Registers<W, N>,
+    pub opcode: usize,
+    pub args: Args<W>,
+    pub after: Registers<W, N>,
+}
+
+impl<W: Word, const N: usize> Sample<W, N> {
+    /// Whether `op` turns `before` into `after`, with checked arithmetic.
+    /// Register arguments out of range never match.
+    pub fn matches(&self, op: &Operation<W, N>) -> bool {
+        op.execute(self.args, self.before, Arithmetic::Checked) == Ok(self.after)
+    }
+
+    /// The operations that agree with the sample
+    pub fn candidates(&self) -> Vec<Operation<W, N>> {
+        Operation::OPS
+            .iter()
+            .filter(|op| self.matches(op))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Maps every opcode seen in `samples` to the only operation consistent with
+/// all of them. Fails with `ErrorKind::AmbiguousOpcodes`, listing the
+/// remaining candidates, if the samples do not pin down every opcode.
+pub fn infer_opcodes<W: Word, const N: usize>(
+    samples: &[Sample<W, N>],
+) -> Result<HashMap<usize, Operation<W, N>>> {
+    let mut candidates: BTreeMap<usize, Vec<Operation<W, N>>> = BTreeMap::new();
+    for sample in samples {
+        candidates
+            .entry(sample.opcode)
+            .or_insert_with(|| Operation::OPS.to_vec())
+            .retain(|op| sample.matches(op));
+    }
+    let mut resolved = HashMap::new();
+    while let Some((opcode, op)) = candidates
+        .iter()
+        .find(|(_, ops)| ops.len() == 1)
+        .map(|(&opcode, ops)| (opcode, ops[0]))
+    {
+        resolved.insert(opcode, op);
+        candidates.remove(&opcode);
+        for ops in candidates.values_mut() {
+            ops.retain(|other| other.name() != op.name());
+        }
+    }
+    if !candidates.is_empty() {
+        let names = candidates
+            .into_iter()
+            .map(|(opcode, ops)| (opcode, ops.iter().map(|op| op.name()).collect()))
+            .collect();
+        bail!(ErrorKind::AmbiguousOpcodes(names));
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::errors::ErrorKind;
+    use super::{infer_opcodes, Sample};
+
+    #[test]
+    fn test_infer_opcodes() {
+        // mulr 2 1 2, with 9 as the opcode
+        let mulr = Sample {
+            before: [3, 2, 1, 1],
+            opcode: 9,
+            args: (2, 1, 2),
+            after: [3, 2, 2, 1],
+        };
+        assert_eq!(3, mulr.candidates().len());
+        // seti 2 _ 3 as opcode 4, which rules out seti for opcode 9
+        let seti = Sample {
+            before: [0, 10, 20, 0],
+            opcode: 4,
+            args: (2, 1, 3),
+            after: [0, 10, 20, 2],
+        };
+        match infer_opcodes(&[mulr, seti]).unwrap_err().kind() {
+            ErrorKind::AmbiguousOpcodes(candidates) => {
+                assert_eq!(vec!["addi", "mulr"], candidates[&9]);
+                assert!(!candidates.contains_key(&4));
+            }
+            kind => panic!("unexpected error {:?}", kind),
+        }
+        // addi 1 3 0 as opcode 2 leaves only mulr for opcode 9
+        let addi = Sample {
+            before: [0, 10, 20, 30],
+            opcode: 2,
+            args: (1, 3, 0),
+            after: [13, 10, 20, 30],
+        };
+        let opcodes = infer_opcodes(&[mulr, seti, addi]).unwrap();
+        assert_eq!("mulr", opcodes[&9].name());
+        assert_eq!("seti", opcodes[&4].name());
+        assert_eq!("addi", opcodes[&2].name());
+    }
+}
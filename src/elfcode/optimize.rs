@@ -0,0 +1,381 @@
+//! Recognition of hot loops that can be executed natively.
+//!
+//! Patterns are matched against the instructions with the registers they use
+//! as variables, so they apply to any register allocation and to programs
+//! placed at any offset. Commutative operations match either argument order.
+
+use super::{Arithmetic, Fault, Instruction, Machine, Registers};
+
+/// A loop which `Machine::step` executes as a single step, when the
+/// instruction pointer reaches its first instruction.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Idiom {
+    /// `sum += σ(n)`, by testing whether `a * b == n` for all `a, b <= n`
+    DivisorSum {
+        a: usize,
+        b: usize,
+        tmp: usize,
+        n: usize,
+        sum: usize,
+        exit: usize,
+    },
+    /// `quotient = dividend / divisor`, by counting up until
+    /// `(quotient + 1) * divisor > dividend`
+    Divide {
+        quotient: usize,
+        tmp: usize,
+        dividend: usize,
+        divisor: usize,
+        exit: usize,
+    },
+}
+
+impl Idiom {
+    /// Sets the registers to what they would be once the loop exits, and
+    /// returns `true`. If any addition or multiplication of the loop would
+    /// overflow, checked arithmetic fails with `Fault::Overflow` just like the
+    /// loop does. Wrapping and saturating arithmetic leave the registers
+    /// alone and return `false`, so that the loop runs as written.
+    pub fn apply(
+        &self,
+        registers: &mut Registers,
+        ip: usize,
+        arithmetic: Arithmetic,
+    ) -> Result<bool, Fault> {
+        let overflow = || match arithmetic {
+            Arithmetic::Checked => Err(Fault::Overflow),
+            _ => Ok(false),
+        };
+        match *self {
+            Idiom::DivisorSum {
+                a,
+                b,
+                tmp,
+                n,
+                sum,
+                exit,
+            } => {
+                let n = registers[n];
+                // the largest product the loop computes is `n * n`, and its
+                // counters end at `n + 1`
+                let total = match n.max(1).checked_mul(n.max(1)).and_then(|_| divisor_sum(n)) {
+                    Some(divisors) => registers[sum].checked_add(divisors),
+                    None => None,
+                };
+                let total = match total {
+                    Some(total) => total,
+                    None => return overflow(),
+                };
+                let past = n.max(1) + 1;
+                registers[sum] = total;
+                registers[a] = past;
+                registers[b] = past;
+                registers[tmp] = 1;
+                registers[ip] = exit;
+            }
+            Idiom::Divide {
+                quotient,
+                tmp,
+                dividend,
+                divisor,
+                exit,
+            } => {
+                let result = registers[dividend] / divisor;
+                // the loop stops after computing `(result + 1) * divisor`
+                let last = result
+                    .checked_add(1)
+                    .and_then(|next| next.checked_mul(divisor));
+                if last.is_none() {
+                    return overflow();
+                }
+                registers[quotient] = result;
+                registers[tmp] = 1;
+                registers[ip] = exit;
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// The sum of the divisors of `n`, or `None` if it does not fit in a word
+fn divisor_sum(n: usize) -> Option<usize> {
+    let mut sum: usize = 0;
+    let mut q = 1;
+    while q <= n / q {
+        if n % q == 0 {
+            sum = sum.checked_add(q)?;
+            if q != n / q {
+                sum = sum.checked_add(n / q)?;
+            }
+        }
+        q += 1;
+    }
+    Some(sum)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Pat {
+    /// A register, bound on first use and distinct from all other registers
+    Reg(usize),
+    /// The instruction pointer register
+    Ip,
+    /// This exact immediate
+    Imm(usize),
+    /// The index of the pattern's first instruction plus an offset
+    Rel(usize),
+    /// An immediate, bound on first use
+    Const(usize),
+    Any,
+}
+
+use self::Pat::*;
+
+type Pattern = [(&'static str, [Pat; 3])];
+
+const VARS: usize = 6;
+
+// for a in 1..=n { for b in 1..=n { if a * b == n { sum += a } } }
+const DIVISOR_SUM: [(&str, [Pat; 3]); 15] = [
+    ("seti", [Imm(1), Any, Reg(0)]),
+    ("seti", [Imm(1), Any, Reg(1)]),
+    ("mulr", [Reg(0), Reg(1), Reg(2)]),
+    ("eqrr", [Reg(2), Reg(3), Reg(2)]),
+    ("addr", [Reg(2), Ip, Ip]),
+    ("addi", [Ip, Imm(1), Ip]),
+    ("addr", [Reg(0), Reg(4), Reg(4)]),
+    ("addi", [Reg(1), Imm(1), Reg(1)]),
+    ("gtrr", [Reg(1), Reg(3), Reg(2)]),
+    ("addr", [Ip, Reg(2), Ip]),
+    ("seti", [Rel(1), Any, Ip]),
+    ("addi", [Reg(0), Imm(1), Reg(0)]),
+    ("gtrr", [Reg(0), Reg(3), Reg(2)]),
+    ("addr", [Reg(2), Ip, Ip]),
+    ("seti", [Rel(0), Any, Ip]),
+];
+
+// q = 0; while (q + 1) * d <= x { q += 1 }
+const DIVIDE: [(&str, [Pat; 3]); 9] = [
+    ("seti", [Imm(0), Any, Reg(0)]),
+    ("addi", [Reg(0), Imm(1), Reg(1)]),
+    ("muli", [Reg(1), Const(0), Reg(1)]),
+    ("gtrr", [Reg(1), Reg(2), Reg(1)]),
+    ("addr", [Reg(1), Ip, Ip]),
+    ("addi", [Ip, Imm(1), Ip]),
+    ("seti", [Rel(8), Any, Ip]),
+    ("addi", [Reg(0), Imm(1), Reg(0)]),
+    ("seti", [Rel(0), Any, Ip]),
+];
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Bindings {
+    regs: [Option<usize>; VARS],
+    consts: [Option<usize>; VARS],
+}
+
+impl Bindings {
+    fn bind(&mut self, pat: Pat, arg: usize, ip: usize, start: usize) -> bool {
+        match pat {
+            Reg(var) => match self.regs[var] {
+                Some(reg) => reg == arg,
+                None => {
+                    if arg == ip || self.regs.contains(&Some(arg)) {
+                        return false;
+                    }
+                    self.regs[var] = Some(arg);
+                    true
+                }
+            },
+            Ip => arg == ip,
+            Imm(value) => arg == value,
+            Rel(offset) => arg == start + offset,
+            Const(var) => *self.consts[var].get_or_insert(arg) == arg,
+            Any => true,
+        }
+    }
+
+    fn reg(&self, var: usize) -> usize {
+        self.regs[var].expect("unbound register")
+    }
+}
+
+fn match_pattern(
+    instructions: &[Instruction],
+    ip: usize,
+    start: usize,
+    pattern: &Pattern,
+) -> Option<Bindings> {
+    fn go(
+        instructions: &[Instruction],
+        ip: usize,
+        start: usize,
+        pattern: &Pattern,
+        bindings: Bindings,
+    ) -> Option<Bindings> {
+        let index = instructions.len() - pattern.len();
+        let (name, pats) = match pattern.first() {
+            None => return Some(bindings),
+            Some(&first) => first,
+        };
+        let (op, (a, b, c)) = instructions[index];
+        if op.name() != name {
+            return None;
+        }
+        let commutative = ["addr", "mulr", "banr", "borr", "eqrr"].contains(&name);
+        let orders: &[(usize, usize)] = if commutative {
+            &[(a, b), (b, a)]
+        } else {
+            &[(a, b)]
+        };
+        for &(a, b) in orders {
+            let mut next = bindings;
+            if next.bind(pats[0], a, ip, start)
+                && next.bind(pats[1], b, ip, start)
+                && next.bind(pats[2], c, ip, start)
+            {
+                if let Some(result) = go(instructions, ip, start, &pattern[1..], next) {
+                    return Some(result);
+                }
+            }
+        }
+        None
+    }
+    let window = instructions.get(start..start + pattern.len())?;
+    go(window, ip, start, pattern, Bindings::default())
+}
+
+impl Machine {
+    /// Finds the loops that `Idiom` knows how to execute natively. Returns
+    /// the number of loops found.
+    pub fn optimize(&mut self) -> usize {
+        self.idioms = (0..self.instructions.len())
+            .map(|start| self.recognize(start))
+            .collect();
+        self.idioms.iter().filter(|idiom| idiom.is_some()).count()
+    }
+
+    fn recognize(&self, start: usize) -> Option<Idiom> {
+        let ip = self.instruction_pointer;
+        if let Some(m) = match_pattern(&self.instructions, ip, start, &DIVISOR_SUM) {
+            return Some(Idiom::DivisorSum {
+                a: m.reg(0),
+                b: m.reg(1),
+                tmp: m.reg(2),
+                n: m.reg(3),
+                sum: m.reg(4),
+                exit: start + DIVISOR_SUM.len(),
+            });
+        }
+        if let Some(m) = match_pattern(&self.instructions, ip, start, &DIVIDE) {
+            match m.consts[0] {
+                Some(divisor) if divisor > 0 => {
+                    return Some(Idiom::Divide {
+                        quotient: m.reg(0),
+                        tmp: m.reg(1),
+                        dividend: m.reg(2),
+                        divisor,
+                        exit: start + DIVIDE.len(),
+                    })
+                }
+                _ => (),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Arithmetic, Machine};
+    use super::Idiom;
+
+    #[test]
+    fn test_divisor_sum() {
+        let original: Machine = include_str!("../../fixtures/day_19.in").parse().unwrap();
+        let mut optimized = original.clone();
+        assert_eq!(1, optimized.optimize());
+        assert_eq!(
+            Some(Idiom::DivisorSum {
+                a: 3,
+                b: 5,
+                tmp: 1,
+                n: 2,
+                sum: 0,
+                exit: 16,
+            }),
+            optimized.idioms[1]
+        );
+        let mut original = original;
+        while original.next().unwrap() {}
+        while optimized.next().unwrap() {}
+        assert_eq!(original.registers[0], optimized.registers[0]);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let mut machine: Machine = include_str!("../../fixtures/day_21.in").parse().unwrap();
+        machine.optimize();
+        // divide r[1] = usize::MAX by 256 on line 17
+        machine.registers[1] = usize::MAX;
+        machine.registers[machine.instruction_pointer] = 17;
+        let mut wrapping = machine.clone();
+        assert!(machine.step().is_err());
+        // the loop wraps around instead of stopping, so it has to run
+        wrapping.arithmetic = Arithmetic::Wrapping;
+        assert!(wrapping.step().is_ok());
+        assert_eq!(18, wrapping.line());
+
+        let mut machine: Machine = include_str!("../../fixtures/day_19.in").parse().unwrap();
+        machine.optimize();
+        // the divisor sum of r[2] on line 1 computes r[2] * r[2]
+        machine.registers[2] = 1 << 40;
+        machine.registers[machine.instruction_pointer] = 1;
+        let mut wrapping = machine.clone();
+        assert!(machine.step().is_err());
+        wrapping.arithmetic = Arithmetic::Wrapping;
+        assert!(wrapping.step().is_ok());
+        assert_eq!(2, wrapping.line());
+
+        // 2^32 - 1 = 3 * 5 * 17 * 257 * 65537
+        machine.registers[2] = (1 << 32) - 1;
+        machine.registers[0] = 0;
+        assert!(machine.step().is_ok());
+        assert_eq!(16, machine.line());
+        assert_eq!(4 * 6 * 18 * 258 * 65538, machine.registers[0]);
+    }
+
+    #[test]
+    fn test_divisor_sums() {
+        use super::divisor_sum;
+        assert_eq!(Some(1), divisor_sum(1));
+        assert_eq!(Some(1 + 2 + 3 + 6), divisor_sum(6));
+        assert_eq!(Some(1 + 7 + 49), divisor_sum(49));
+        // the largest n whose square fits in 64 bits
+        assert_eq!(Some(7_304_603_328), divisor_sum((1 << 32) - 1));
+    }
+
+    #[test]
+    fn test_divide() {
+        let original: Machine = include_str!("../../fixtures/day_21.in").parse().unwrap();
+        let mut optimized = original.clone();
+        assert_eq!(1, optimized.optimize());
+        assert_eq!(
+            Some(Idiom::Divide {
+                quotient: 5,
+                tmp: 4,
+                dividend: 1,
+                divisor: 256,
+                exit: 26,
+            }),
+            optimized.idioms[17]
+        );
+        // compare the state at the first few checks against r[0]
+        let mut original = original;
+        for _ in 0..3 {
+            original.next().unwrap();
+            optimized.next().unwrap();
+            original.run_until(1_000_000, |m| m.line() == 28).unwrap();
+            optimized.run_until(1_000_000, |m| m.line() == 28).unwrap();
+            assert_eq!(original.registers, optimized.registers);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Execution counts for finding hot loops.
+
+use std::collections::BTreeMap;
+
+use super::{Machine, Step, REGISTER_COUNT};
+
+/// Counts collected by `Machine::step` while profiling is enabled
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Profile {
+    /// Number of times each instruction was executed
+    pub hits: Vec<u64>,
+    /// For each register, how many times each value was written to it
+    pub writes: Vec<BTreeMap<usize, u64>>,
+}
+
+impl Profile {
+    pub fn new(lines: usize) -> Profile {
+        Profile {
+            hits: vec![0; lines],
+            writes: vec![BTreeMap::new(); REGISTER_COUNT],
+        }
+    }
+
+    pub fn record(&mut self, step: &Step) {
+        if step.line >= self.hits.len() {
+            self.hits.resize(step.line + 1, 0);
+        }
+        self.hits[step.line] += 1;
+        let (_, (_, _, c)) = step.instruction;
+        *self.writes[c].entry(step.after[c]).or_insert(0) += 1;
+    }
+
+    pub fn total(&self) -> u64 {
+        self.hits.iter().sum()
+    }
+
+    /// The `n` values written most often to `register`, most frequent first
+    /// and smallest first among equally frequent values
+    pub fn most_written(&self, register: usize, n: usize) -> Vec<(usize, u64)> {
+        let mut values: Vec<(usize, u64)> = self.writes[register]
+            .iter()
+            .map(|(&value, &count)| (value, count))
+            .collect();
+        values.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        values.truncate(n);
+        values
+    }
+}
+
+impl Machine {
+    /// Starts counting executed instructions and register writes afresh
+    pub fn enable_profiling(&mut self) {
+        self.profile = Some(Profile::new(self.instructions.len()));
+    }
+
+    /// The `deparse` listing, with every line prefixed by its execution count
+    /// and share of all executed instructions. Empty if profiling is off.
+    pub fn annotate(&self) -> String {
+        let profile = match self.profile {
+            None => return String::new(),
+            Some(ref profile) => profile,
+        };
+        let total = profile.total().max(1) as f64;
+        self.deparse()
+            .lines()
+            .enumerate()
+            .map(|(line, listing)| {
+                let hits = profile.hits.get(line).cloned().unwrap_or(0);
+                format!(
+                    "{:>12} {:>5.1}%  {}\n",
+                    hits,
+                    100.0 * hits as f64 / total,
+                    listing
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Machine;
+
+    #[test]
+    fn test_profile() {
+        let mut machine: Machine = r"#ip 5
+seti 0 0 0
+addi 0 1 0
+gtri 0 2 1
+addr 1 5 5
+seti 0 0 5"
+            .parse()
+            .unwrap();
+        machine.enable_profiling();
+        while machine.next().unwrap() {}
+        let profile = machine.profile.clone().unwrap();
+        assert_eq!(vec![1, 3, 3, 3, 2], profile.hits);
+        assert_eq!(vec![(0, 1), (1, 1), (2, 1)], profile.most_written(0, 3));
+        assert_eq!(vec![(0, 2), (1, 1)], profile.most_written(1, 5));
+        assert_eq!(
+            Some("           3  25.0%  1: r[0] = r[0] + 1;"),
+            machine.annotate().lines().nth(1)
+        );
+    }
+}
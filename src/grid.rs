@@ -0,0 +1,173 @@
+//! A grid with signed coordinates that grows as it is written to.
+
+use Mat;
+
+/// Where a frozen `Grid` ended up: the grid coordinates of the `Mat`'s
+/// top left element
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct Offset {
+    pub row: isize,
+    pub col: isize,
+}
+
+impl Offset {
+    /// The `Mat` position of grid coordinates `(row, col)`
+    pub fn to_mat(&self, row: isize, col: isize) -> (usize, usize) {
+        assert!(row >= self.row && col >= self.col);
+        ((row - self.row) as usize, (col - self.col) as usize)
+    }
+
+    /// The grid coordinates of `Mat` position `(row, col)`
+    pub fn to_grid(&self, row: usize, col: usize) -> (isize, isize) {
+        (self.row + row as isize, self.col + col as isize)
+    }
+}
+
+/// A dense grid over `isize` coordinates. Every cell holds `fill` until it
+/// is written, and writing outside the allocated area grows it in that
+/// direction.
+#[derive(Debug, Clone)]
+pub struct Grid<T: Clone> {
+    fill: T,
+    /// Allocated area, as its top left corner and size
+    start: (isize, isize),
+    size: (usize, usize),
+    data: Vec<T>,
+    /// Smallest and largest written coordinates
+    written: Option<((isize, isize), (isize, isize))>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(fill: T) -> Grid<T> {
+        Grid {
+            fill,
+            start: (0, 0),
+            size: (0, 0),
+            data: Vec::new(),
+            written: None,
+        }
+    }
+
+    fn index(&self, row: isize, col: isize) -> Option<usize> {
+        let (r, c) = (row - self.start.0, col - self.start.1);
+        if r < 0 || c < 0 || r as usize >= self.size.0 || c as usize >= self.size.1 {
+            None
+        } else {
+            Some(r as usize * self.size.1 + c as usize)
+        }
+    }
+
+    pub fn get(&self, row: isize, col: isize) -> &T {
+        match self.index(row, col) {
+            Some(i) => &self.data[i],
+            None => &self.fill,
+        }
+    }
+
+    pub fn set(&mut self, row: isize, col: isize, val: T) {
+        let i = match self.index(row, col) {
+            Some(i) => i,
+            None => {
+                self.grow(row, col);
+                self.index(row, col).unwrap()
+            }
+        };
+        self.data[i] = val;
+        self.written = Some(match self.written {
+            None => ((row, col), (row, col)),
+            Some((min, max)) => (
+                (min.0.min(row), min.1.min(col)),
+                (max.0.max(row), max.1.max(col)),
+            ),
+        });
+    }
+
+    /// Reallocates so that `(row, col)` fits, at least doubling the size in
+    /// each direction that has to grow
+    fn grow(&mut self, row: isize, col: isize) {
+        let (rows, cols) = (self.size.0 as isize, self.size.1 as isize);
+        let extend = |lo: isize, len: isize, x: isize| {
+            let slack = len.max(4);
+            let new_lo = if x < lo { x - slack } else { lo };
+            let new_hi = if x >= lo + len { x + slack } else { lo + len };
+            (new_lo, (new_hi - new_lo) as usize)
+        };
+        let (row_start, new_rows) = if rows == 0 {
+            (row, 1)
+        } else {
+            extend(self.start.0, rows, row)
+        };
+        let (col_start, new_cols) = if cols == 0 {
+            (col, 1)
+        } else {
+            extend(self.start.1, cols, col)
+        };
+        let mut data = vec![self.fill.clone(); new_rows * new_cols];
+        for r in 0..self.size.0 {
+            for c in 0..self.size.1 {
+                let (grid_row, grid_col) = (self.start.0 + r as isize, self.start.1 + c as isize);
+                let i =
+                    (grid_row - row_start) as usize * new_cols + (grid_col - col_start) as usize;
+                data[i] = self.data[r * self.size.1 + c].clone();
+            }
+        }
+        self.start = (row_start, col_start);
+        self.size = (new_rows, new_cols);
+        self.data = data;
+    }
+
+    /// The smallest and largest coordinates written so far, as
+    /// `((row_min, col_min), (row_max, col_max))`
+    pub fn bounds(&self) -> Option<((isize, isize), (isize, isize))> {
+        self.written
+    }
+
+    /// Copies the written area into a `Mat`, with `margin.0` rows of `fill`
+    /// added above and below and `margin.1` columns on either side
+    pub fn freeze(&self, margin: (usize, usize)) -> (Mat<T>, Offset) {
+        let ((row_min, col_min), (row_max, col_max)) = match self.written {
+            None => return (Mat::new(0, 0, self.fill.clone()), Offset::default()),
+            Some(bounds) => bounds,
+        };
+        let offset = Offset {
+            row: row_min - margin.0 as isize,
+            col: col_min - margin.1 as isize,
+        };
+        let rows = (row_max - row_min) as usize + 1 + 2 * margin.0;
+        let cols = (col_max - col_min) as usize + 1 + 2 * margin.1;
+        let mut mat = Mat::new(rows, cols, self.fill.clone());
+        for row in 0..rows {
+            for col in 0..cols {
+                let (grid_row, grid_col) = offset.to_grid(row, col);
+                mat.set(row, col, self.get(grid_row, grid_col).clone());
+            }
+        }
+        (mat, offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Grid, Offset};
+
+    #[test]
+    fn test_grow_and_freeze() {
+        let mut grid = Grid::new('.');
+        grid.set(0, 0, 'a');
+        grid.set(-3, 2, 'b');
+        grid.set(1, -20, 'c');
+        grid.set(0, 0, 'd');
+        assert_eq!(&'b', grid.get(-3, 2));
+        assert_eq!(&'.', grid.get(100, -100));
+        assert_eq!(Some(((-3, -20), (1, 2))), grid.bounds());
+
+        let (mat, offset) = grid.freeze((1, 0));
+        assert_eq!(Offset { row: -4, col: -20 }, offset);
+        assert_eq!((7, 23), (mat.rows(), mat.cols()));
+        assert_eq!((4, 20), offset.to_mat(0, 0));
+        assert_eq!(&'d', mat.get(4, 20));
+        assert_eq!(&'b', mat.get(1, 22));
+        assert_eq!(&'c', mat.get(5, 0));
+        assert_eq!((-3, 2), offset.to_grid(1, 22));
+    }
+}
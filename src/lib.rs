@@ -1,14 +1,44 @@
+extern crate chrono;
 #[macro_use]
 extern crate error_chain;
+extern crate gif;
+extern crate nalgebra as na;
+extern crate png;
+extern crate regex;
 
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::fmt;
 use std::hash::{Hash, Hasher};
 
+pub mod answers;
+pub mod bench;
+pub mod counter;
+pub mod cycle;
+pub mod days;
 pub mod elfcode;
+pub mod grid;
+pub mod parse;
+pub mod record;
+pub mod render;
+pub mod search;
+pub mod solution;
 
-mod errors {
+pub use counter::Counter;
+pub use grid::Grid;
+
+pub mod errors {
     error_chain! {
+        links {
+            Elfcode(::elfcode::errors::Error, ::elfcode::errors::ErrorKind);
+        }
+
+        foreign_links {
+            Io(::std::io::Error);
+            Png(::png::EncodingError);
+            Gif(::gif::EncodingError);
+        }
+
         errors {
             ParseValueError(s: String) {
                 description("unable to parse value"),
@@ -19,25 +49,36 @@ mod errors {
                 description("invalid matrix dimensions"),
                 display("invalid number of {}: Expected {} but got {}", dim, expected, actual)
             }
+
+            ParseInputError(line: usize, col: usize) {
+                description("unable to parse input"),
+                display("unable to parse input at line {}, column {}", line, col)
+            }
         }
     }
 }
 
 use errors::*;
 
+#[deprecated(note = "use Counter, which also finds the most common values")]
 pub fn count_by_value<'a, T: 'a, I>(data: I) -> HashMap<T, u32>
 where
     I: Iterator<Item = T>,
     T: Eq + std::hash::Hash,
 {
-    let mut result = HashMap::new();
-    for value in data {
-        let count: u32 = *result.get(&value).unwrap_or(&0);
-        result.insert(value, count + 1);
-    }
-    return result;
+    let counter: Counter<T> = data.collect();
+    counter
+        .into_map()
+        .into_iter()
+        .map(|(value, count)| {
+            let count = u32::try_from(count).expect("more than u32::MAX copies of a value");
+            (value, count)
+        })
+        .collect()
 }
 
+/// A dense matrix stored in row-major order, so that iterating over rows and
+/// then columns visits contiguous memory
 #[derive(Debug, Clone)]
 pub struct Mat<T: Clone> {
     rows: usize,
@@ -71,14 +112,15 @@ impl<T: Clone> Mat<T> {
 
     pub fn get(&self, row: usize, col: usize) -> &T {
         debug_assert!(row < self.rows && col < self.cols);
-        &self.data[row + col * self.rows]
+        &self.data[row * self.cols + col]
     }
 
     pub fn set(&mut self, row: usize, col: usize, val: T) {
         debug_assert!(row < self.rows && col < self.cols);
-        self.data[row + col * self.rows] = val;
+        self.data[row * self.cols + col] = val;
     }
 
+    /// All elements in reading order
     pub fn iter<'a>(&'a self) -> impl Iterator<Item = &'a T> {
         self.data.iter()
     }
@@ -90,13 +132,253 @@ impl<T: Clone> Mat<T> {
     pub fn cols(&self) -> usize {
         self.cols
     }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        assert!(row < self.rows);
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        assert!(row < self.rows);
+        &mut self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// The elements of column `col`, from top to bottom
+    pub fn col(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.cols);
+        // a matrix without rows has no data to start the column in
+        let data = self.data.get(col..).unwrap_or(&[]);
+        data.iter().step_by(self.cols).take(self.rows)
+    }
+
+    pub fn rows_iter(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.rows).map(move |row| self.row(row))
+    }
+
+    pub fn cols_iter(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.cols).map(move |col| self.col(col))
+    }
+
+    /// The up to 4 positions sharing an edge with `(row, col)`, in reading order
+    pub fn neighbors4(&self, row: usize, col: usize) -> Neighbors {
+        Neighbors::new(self, row, col, &OFFSETS_4)
+    }
+
+    /// The up to 8 positions sharing an edge or a corner with `(row, col)`, in
+    /// reading order
+    pub fn neighbors8(&self, row: usize, col: usize) -> Neighbors {
+        Neighbors::new(self, row, col, &OFFSETS_8)
+    }
+
+    /// The `rows` by `cols` sub-matrix whose top left corner is at `(row, col)`
+    pub fn window(&self, row: usize, col: usize, rows: usize, cols: usize) -> Window<'_, T> {
+        assert!(row + rows <= self.rows && col + cols <= self.cols);
+        Window {
+            mat: self,
+            row,
+            col,
+            rows,
+            cols,
+        }
+    }
+
+    /// Parses one element per character, with one row per line. Every line
+    /// has to be as long as the first.
+    pub fn parse_with<F>(data: &str, parse: F) -> Result<Mat<T>>
+    where
+        F: FnMut(char) -> Result<T>,
+    {
+        Mat::parse_lines(data, None, parse)
+    }
+
+    /// Like `parse_with`, but lines may have different lengths, and those
+    /// shorter than the longest are padded with `fill` on the right
+    pub fn parse_padded<F>(data: &str, fill: T, parse: F) -> Result<Mat<T>>
+    where
+        F: FnMut(char) -> Result<T>,
+    {
+        Mat::parse_lines(data, Some(fill), parse)
+    }
+
+    fn parse_lines<F>(data: &str, fill: Option<T>, mut parse: F) -> Result<Mat<T>>
+    where
+        F: FnMut(char) -> Result<T>,
+    {
+        let lengths: Vec<usize> = data.lines().map(|line| line.chars().count()).collect();
+        let cols = match fill {
+            Some(_) => lengths.iter().cloned().max().unwrap_or(0),
+            None => lengths.first().cloned().unwrap_or(0),
+        };
+        let mut elements = Vec::with_capacity(lengths.len() * cols);
+        for (row, line) in data.lines().enumerate() {
+            if fill.is_none() && lengths[row] != cols {
+                let e = Error::from(ErrorKind::ParseMatrixDimensionsError(
+                    "columns".to_owned(),
+                    cols,
+                    lengths[row],
+                ));
+                let col = lengths[row].min(cols);
+                return Err(e).chain_err(|| ErrorKind::ParseInputError(row + 1, col + 1));
+            }
+            for (col, c) in line.chars().enumerate() {
+                let element = parse(c).chain_err(|| ErrorKind::ParseInputError(row + 1, col + 1))?;
+                elements.push(element);
+            }
+            if let Some(ref fill) = fill {
+                elements.resize(elements.len() + cols - lengths[row], fill.clone());
+            }
+        }
+        Ok(Mat {
+            rows: lengths.len(),
+            cols,
+            data: elements,
+        })
+    }
+
+    /// One character per element and one line per row, the inverse of
+    /// `parse_with`
+    pub fn to_string_with<F>(&self, mut render: F) -> String
+    where
+        F: FnMut(&T) -> char,
+    {
+        let mut result = String::with_capacity(self.rows * (self.cols + 1));
+        for row in self.rows_iter() {
+            result.extend(row.iter().map(&mut render));
+            result.push('\n');
+        }
+        result
+    }
+}
+
+const OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// Iterator over the positions around a cell, created by `Mat::neighbors4`
+/// and `Mat::neighbors8`
+#[derive(Debug, Clone)]
+pub struct Neighbors {
+    center: (usize, usize),
+    size: (usize, usize),
+    offsets: &'static [(isize, isize)],
+    wrapping: bool,
+}
+
+impl Neighbors {
+    fn new<T: Clone>(
+        mat: &Mat<T>,
+        row: usize,
+        col: usize,
+        offsets: &'static [(isize, isize)],
+    ) -> Neighbors {
+        debug_assert!(row < mat.rows && col < mat.cols);
+        Neighbors {
+            center: (row, col),
+            size: (mat.rows, mat.cols),
+            offsets,
+            wrapping: false,
+        }
+    }
+
+    /// Treats the matrix as a torus, so that positions past an edge wrap
+    /// around to the opposite edge instead of being skipped. Positions are
+    /// then in the order of their offsets rather than in reading order, and
+    /// may repeat in matrices with fewer than 3 rows or columns.
+    pub fn wrapping(self) -> Neighbors {
+        Neighbors {
+            wrapping: true,
+            ..self
+        }
+    }
+}
+
+impl Iterator for Neighbors {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        let shift = |x: usize, dx: isize, len: usize, wrapping: bool| {
+            let y = x as isize + dx;
+            if wrapping {
+                Some(y.rem_euclid(len as isize) as usize)
+            } else if y < 0 || y >= len as isize {
+                None
+            } else {
+                Some(y as usize)
+            }
+        };
+        while let Some((&(drow, dcol), rest)) = self.offsets.split_first() {
+            self.offsets = rest;
+            let row = shift(self.center.0, drow, self.size.0, self.wrapping);
+            let col = shift(self.center.1, dcol, self.size.1, self.wrapping);
+            if let (Some(row), Some(col)) = (row, col) {
+                return Some((row, col));
+            }
+        }
+        None
+    }
+}
+
+/// A rectangular view into a `Mat`, created by `Mat::window`
+#[derive(Debug, Clone)]
+pub struct Window<'a, T: 'a + Clone> {
+    mat: &'a Mat<T>,
+    row: usize,
+    col: usize,
+    rows: usize,
+    cols: usize,
+}
+
+impl<'a, T: Clone> Window<'a, T> {
+    pub fn get(&self, row: usize, col: usize) -> &'a T {
+        debug_assert!(row < self.rows && col < self.cols);
+        self.mat.get(self.row + row, self.col + col)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row(&self, row: usize) -> &'a [T] {
+        assert!(row < self.rows);
+        &self.mat.row(self.row + row)[self.col..self.col + self.cols]
+    }
+
+    pub fn rows_iter(&self) -> impl Iterator<Item = &'a [T]> {
+        let window = self.clone();
+        (0..self.rows).map(move |row| window.row(row))
+    }
+
+    /// All elements in reading order
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> {
+        self.rows_iter().flat_map(|row| row.iter())
+    }
+
+    pub fn to_mat(&self) -> Mat<T> {
+        Mat {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.iter().cloned().collect(),
+        }
+    }
 }
 
 impl<T: Clone + fmt::Display> fmt::Display for Mat<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for row in 0..self.rows {
-            for col in 0..self.cols {
-                write!(f, "{}", self.get(row, col))?;
+        for row in self.rows_iter() {
+            for value in row {
+                write!(f, "{}", value)?;
             }
             write!(f, "\n")?;
         }
@@ -104,54 +386,107 @@ impl<T: Clone + fmt::Display> fmt::Display for Mat<T> {
     }
 }
 
-impl<T: Clone + fmt::Debug + Default + std::str::FromStr> std::str::FromStr for Mat<T> {
+impl<T: Clone + std::str::FromStr> std::str::FromStr for Mat<T> {
     type Err = errors::Error;
 
     fn from_str(data: &str) -> Result<Self> {
-        let rows = data.lines().count();
-        let cols = data.lines().next().unwrap_or("").len();
-        let mut mat = Mat::new(rows, cols, T::default());
-        let mut last_row = 0;
-        for (row, line) in data.lines().enumerate() {
-            if row >= rows {
-                bail!(ErrorKind::ParseMatrixDimensionsError(
-                    "rows".to_owned(),
-                    row,
-                    rows
-                ));
-            }
-            let mut last_col = 0;
-            for (col, c) in line.chars().enumerate() {
-                let s = &c.to_string();
-                let v: T = s
-                    .parse()
-                    .map_err(|_| ErrorKind::ParseValueError(s.to_owned()))?;
-                if col >= cols {
-                    bail!(ErrorKind::ParseMatrixDimensionsError(
-                        "columns".to_owned(),
-                        col,
-                        cols
-                    ));
-                }
-                mat.set(row, col, v);
-                last_col = col;
-            }
-            if last_col + 1 != cols {
-                bail!(ErrorKind::ParseMatrixDimensionsError(
-                    "columns".to_owned(),
-                    last_col,
-                    cols
-                ));
-            }
-            last_row = row;
-        }
-        if last_row + 1 != rows {
-            bail!(ErrorKind::ParseMatrixDimensionsError(
-                "rows".to_owned(),
-                last_row,
-                rows
-            ));
-        }
-        Ok(mat)
+        Mat::parse_with(data, |c| {
+            let s = c.to_string();
+            s.parse()
+                .map_err(|_| ErrorKind::ParseValueError(s.clone()).into())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Mat;
+
+    #[test]
+    fn test_views() {
+        let mat: Mat<u32> = "123\n456\n789\n".parse().unwrap();
+        assert_eq!(&[4, 5, 6], mat.row(1));
+        assert_eq!(vec![2, 5, 8], mat.col(1).cloned().collect::<Vec<_>>());
+        let rows: Vec<&[u32]> = mat.rows_iter().collect();
+        assert_eq!(vec![&[1, 2, 3][..], &[4, 5, 6], &[7, 8, 9]], rows);
+        let cols: Vec<Vec<u32>> = mat.cols_iter().map(|c| c.cloned().collect()).collect();
+        assert_eq!(vec![vec![1, 4, 7], vec![2, 5, 8], vec![3, 6, 9]], cols);
+        assert_eq!(
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9],
+            mat.iter().cloned().collect::<Vec<_>>()
+        );
+
+        let window = mat.window(1, 1, 2, 2);
+        assert_eq!(&[5, 6], window.row(0));
+        assert_eq!(8, *window.get(1, 0));
+        assert_eq!("56\n89\n", window.to_mat().to_string());
+        assert_eq!(28, window.iter().sum::<u32>());
+    }
+
+    #[test]
+    fn test_col_without_rows() {
+        let mat = Mat::new(0, 3, 0u32);
+        assert_eq!(0, mat.col(2).count());
+        assert_eq!(3, mat.cols_iter().count());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let mat = Mat::new(3, 4, 0);
+        let all = |n: ::Neighbors| n.collect::<Vec<_>>();
+        assert_eq!(vec![(0, 1), (1, 0)], all(mat.neighbors4(0, 0)));
+        assert_eq!(
+            vec![(0, 2), (1, 1), (1, 3), (2, 2)],
+            all(mat.neighbors4(1, 2))
+        );
+        assert_eq!(vec![(1, 2), (1, 3), (2, 2)], all(mat.neighbors8(2, 3)));
+        assert_eq!(8, mat.neighbors8(1, 1).count());
+        assert_eq!(
+            vec![(2, 0), (0, 3), (0, 1), (1, 0)],
+            all(mat.neighbors4(0, 0).wrapping())
+        );
+        assert_eq!(
+            vec![
+                (1, 2),
+                (1, 3),
+                (1, 0),
+                (2, 2),
+                (2, 0),
+                (0, 2),
+                (0, 3),
+                (0, 0)
+            ],
+            all(mat.neighbors8(2, 3).wrapping())
+        );
+    }
+
+    #[test]
+    fn test_row_mut() {
+        let mut mat = Mat::new(2, 3, 0);
+        mat.row_mut(1).copy_from_slice(&[1, 2, 3]);
+        mat.set(0, 2, 7);
+        assert_eq!("007\n123\n", mat.to_string());
+    }
+
+    #[test]
+    fn test_parse_with() {
+        let wall = |c| match c {
+            '#' => Ok(true),
+            '.' | ' ' => Ok(false),
+            _ => bail!("invalid cell '{}'", c),
+        };
+        let render = |&w: &bool| if w { '#' } else { '.' };
+
+        let mat = Mat::parse_with("#.#\n...\n", wall).unwrap();
+        assert_eq!("#.#\n...\n", mat.to_string_with(render));
+
+        let e = Mat::parse_with("#.#\n.\n", wall).unwrap_err();
+        assert_eq!("unable to parse input at line 2, column 2", e.to_string());
+        let e = Mat::parse_with("#.#\n.x.\n", wall).unwrap_err();
+        assert_eq!("unable to parse input at line 2, column 2", e.to_string());
+
+        let mat = Mat::parse_padded("#\n\n.##\n", false, wall).unwrap();
+        assert_eq!((3, 3), (mat.rows(), mat.cols()));
+        assert_eq!("#..\n...\n.##\n", mat.to_string_with(render));
     }
 }
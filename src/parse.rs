@@ -0,0 +1,129 @@
+//! Parsing puzzle input with errors that say where it broke.
+//!
+//! Parse functions work on slices of the whole input, and these helpers use
+//! the slice being parsed to find its line and column, so a malformed input
+//! fails with a chain like
+//!
+//! ```text
+//! Error: unable to parse claim
+//! Caused by: unable to parse input at line 3, column 11
+//! Caused by: unable to parse value: '3x'
+//! ```
+
+use std::error::Error as StdError;
+use std::iter;
+use std::str::FromStr;
+
+use errors::*;
+
+/// The 1-based line and column where `fragment` starts, or `None` unless
+/// `fragment` is a slice of `data`, like a copy of part of it
+pub fn position(data: &str, fragment: &str) -> Option<(usize, usize)> {
+    let start = data.as_ptr() as usize;
+    let offset = (fragment.as_ptr() as usize).checked_sub(start)?;
+    let end = offset.checked_add(fragment.len())?;
+    if end > data.len() || !data.is_char_boundary(offset) {
+        return None;
+    }
+    let before = &data[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Some((
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    ))
+}
+
+/// An error pointing at the start of `fragment`, a slice of `data`. Without
+/// a position, for fragments that are not, it only says that parsing failed.
+pub fn at(data: &str, fragment: &str) -> ErrorKind {
+    match position(data, fragment) {
+        Some((line, col)) => ErrorKind::ParseInputError(line, col),
+        None => ErrorKind::Msg("unable to parse input".to_owned()),
+    }
+}
+
+/// Parses `fragment`, a slice of `data`, without its surrounding whitespace
+pub fn value<T: FromStr>(data: &str, fragment: &str) -> Result<T> {
+    let trimmed = fragment.trim();
+    trimmed
+        .parse()
+        .map_err(|_| Error::from(ErrorKind::ParseValueError(trimmed.to_owned())))
+        .chain_err(|| at(data, trimmed))
+}
+
+/// Parses every line of `data` with `parse`. Errors that do not already say
+/// where they happened get the position of the start of the line.
+pub fn lines<T, F>(data: &str, mut parse: F) -> Result<Vec<T>>
+where
+    F: FnMut(&str) -> Result<T>,
+{
+    data.lines()
+        .map(|line| {
+            parse(line).or_else(|e| {
+                if located(&e) {
+                    Err(e)
+                } else {
+                    Err(e).chain_err(|| at(data, line))
+                }
+            })
+        })
+        .collect()
+}
+
+/// Whether `e` or any error that caused it has a position. `Error::iter`
+/// walks the same chain, but its causes cannot be downcast. Errors only
+/// return their causes from `source` since error-chain 0.12.4.
+fn located(e: &Error) -> bool {
+    let first: &(dyn StdError + 'static) = e;
+    iter::successors(Some(first), |&cause| cause.source()).any(|cause| {
+        cause.downcast_ref::<Error>().map_or(false, |cause| {
+            matches!(*cause.kind(), ErrorKind::ParseInputError(..))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lines, position, value};
+    use errors::*;
+
+    #[test]
+    fn test_position() {
+        let data = "ab\ncdé f\n\ng";
+        assert_eq!(Some((1, 1)), position(data, data));
+        assert_eq!(Some((2, 1)), position(data, &data[3..]));
+        assert_eq!(Some((2, 5)), position(data, &data[8..]));
+        assert_eq!(Some((4, 1)), position(data, &data[11..]));
+        assert_eq!(Some((4, 2)), position(data, &data[12..]));
+        let copy = data[3..5].to_owned();
+        assert_eq!(None, position(data, &copy));
+        assert_eq!(None, position(&data[3..], data));
+    }
+
+    #[test]
+    fn test_lines() {
+        let data = "1 2\n3 x\n";
+        let parse = |line: &str| -> Result<Vec<u32>> {
+            line.split(' ')
+                .map(|word| value(data, word))
+                .collect::<Result<_>>()
+                .chain_err(|| "unable to parse numbers")
+        };
+        let e = lines(data, parse).unwrap_err();
+        assert_eq!(
+            vec![
+                "unable to parse numbers",
+                "unable to parse input at line 2, column 3",
+                "unable to parse value: 'x'",
+            ],
+            e.iter().map(|cause| cause.to_string()).collect::<Vec<_>>()
+        );
+
+        let e = lines(data, |line| -> Result<()> { bail!("bad line: {}", line) }).unwrap_err();
+        assert_eq!("unable to parse input at line 1, column 1", e.to_string());
+        assert_eq!(
+            Some("bad line: 1 2".to_owned()),
+            e.iter().nth(1).map(|c| c.to_string())
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//! Recording a simulation as it runs, one `Mat` snapshot per step.
+//!
+//! Frames go straight to disk as they are recorded, either as an animated
+//! GIF or as numbered images in a directory, so long simulations do not have
+//! to keep every snapshot around.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use errors::*;
+use gif;
+use render::Rgb;
+use Mat;
+
+/// The path given after `--record` in command line arguments, if any
+pub fn requested(args: &[String]) -> Option<&str> {
+    args.iter()
+        .skip_while(|arg| *arg != "--record")
+        .nth(1)
+        .map(|path| path.as_str())
+}
+
+enum Sink {
+    /// An animated GIF. The encoder needs the size of the image, so it takes
+    /// over the file at the first frame.
+    Gif {
+        file: Option<BufWriter<File>>,
+        encoder: Option<gif::Encoder<BufWriter<File>>>,
+        delay: u16,
+    },
+    /// A directory of images, in the format given by the extension
+    Frames(PathBuf, &'static str),
+}
+
+/// Writes snapshots of a simulation as frames, with every element drawn as a
+/// `scale` by `scale` square of the colour given by `colour`
+pub struct Recorder<F> {
+    sink: Sink,
+    scale: usize,
+    colour: F,
+    every: usize,
+    ticks: usize,
+    frames: usize,
+    size: Option<(usize, usize)>,
+}
+
+impl<F> Recorder<F> {
+    fn new(sink: Sink, scale: usize, colour: F) -> Recorder<F> {
+        Recorder {
+            sink,
+            scale,
+            colour,
+            every: 1,
+            ticks: 0,
+            frames: 0,
+            size: None,
+        }
+    }
+
+    /// Records an animated GIF that shows every frame for `delay` hundredths
+    /// of a second, and loops forever
+    pub fn gif<P: AsRef<Path>>(path: P, scale: usize, delay: u16, colour: F) -> Result<Self> {
+        let path = path.as_ref();
+        let file =
+            File::create(path).chain_err(|| format!("unable to create {}", path.display()))?;
+        let sink = Sink::Gif {
+            file: Some(BufWriter::new(file)),
+            encoder: None,
+            delay,
+        };
+        Ok(Recorder::new(sink, scale, colour))
+    }
+
+    /// Records frames as `000000.png`, `000001.png`, ... in `dir`, which is
+    /// created if needed. With `ppm` set the frames are PPM images instead.
+    pub fn frames<P: AsRef<Path>>(dir: P, scale: usize, ppm: bool, colour: F) -> Result<Self> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir).chain_err(|| format!("unable to create {}", dir.display()))?;
+        let extension = if ppm { "ppm" } else { "png" };
+        Ok(Recorder::new(
+            Sink::Frames(dir.to_owned(), extension),
+            scale,
+            colour,
+        ))
+    }
+
+    /// A GIF if `path` ends in `.gif`, and a directory of PNG frames
+    /// otherwise
+    pub fn open<P: AsRef<Path>>(path: P, scale: usize, colour: F) -> Result<Self> {
+        let path = path.as_ref();
+        if path.extension().map_or(false, |ext| ext == "gif") {
+            Recorder::gif(path, scale, 10, colour)
+        } else {
+            Recorder::frames(path, scale, false, colour)
+        }
+    }
+
+    /// Only keeps one of every `n` recorded snapshots, starting with the first
+    pub fn every(mut self, n: usize) -> Self {
+        self.every = n.max(1);
+        self
+    }
+
+    /// The number of frames written so far
+    pub fn frames_written(&self) -> usize {
+        self.frames
+    }
+
+    /// Takes a snapshot of `mat`. Every snapshot has to have the same size.
+    pub fn record<T: Clone>(&mut self, mat: &Mat<T>) -> Result<()>
+    where
+        F: FnMut(&T) -> Rgb,
+    {
+        let tick = self.ticks;
+        self.ticks += 1;
+        if tick % self.every != 0 {
+            return Ok(());
+        }
+        let size = (mat.rows(), mat.cols());
+        match self.size {
+            None => self.size = Some(size),
+            Some(expected) if expected != size => bail!(
+                "frame {} is {}x{}, but the first one was {}x{}",
+                self.frames,
+                size.0,
+                size.1,
+                expected.0,
+                expected.1
+            ),
+            Some(_) => (),
+        }
+        let (width, height) = (size.1 * self.scale, size.0 * self.scale);
+        match self.sink {
+            Sink::Gif {
+                ref mut file,
+                ref mut encoder,
+                delay,
+            } => {
+                if width > u16::MAX as usize || height > u16::MAX as usize {
+                    bail!("{}x{} is too large for a GIF", width, height);
+                }
+                if let Some(out) = file.take() {
+                    let mut created = gif::Encoder::new(out, width as u16, height as u16, &[])?;
+                    created.set_repeat(gif::Repeat::Infinite)?;
+                    *encoder = Some(created);
+                }
+                let pixels = mat.pixels(self.scale, &mut self.colour);
+                let mut frame =
+                    gif::Frame::from_rgb_speed(width as u16, height as u16, &pixels, 10);
+                frame.delay = delay;
+                encoder.as_mut().unwrap().write_frame(&frame)?;
+            }
+            Sink::Frames(ref dir, extension) => {
+                let path = dir.join(format!("{:06}.{}", self.frames, extension));
+                mat.save_image(path, self.scale, &mut self.colour)?;
+            }
+        }
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Ends the recording. For a GIF this writes the trailer that dropping the
+    /// recorder would otherwise write without reporting errors.
+    pub fn finish(self) -> Result<()> {
+        if let Sink::Gif { file, encoder, .. } = self.sink {
+            if let Some(encoder) = encoder {
+                encoder.into_inner()?.flush()?;
+            } else if file.is_some() {
+                bail!("no frames were recorded");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Recorder;
+    use render::Rgb;
+    use std::env;
+    use std::fs;
+    use Mat;
+
+    #[test]
+    fn test_recorder() {
+        let dir = env::temp_dir().join(format!("aoc2018-record-{}", std::process::id()));
+        let grey = |&v: &u8| Rgb::BLACK.blend(Rgb::WHITE, f64::from(v));
+        let mut mat: Mat<u8> = "01\n10\n".parse().unwrap();
+
+        let mut frames = Recorder::frames(dir.join("frames"), 1, true, grey)
+            .unwrap()
+            .every(2);
+        for _ in 0..5 {
+            frames.record(&mat).unwrap();
+            mat.set(0, 0, 1 - *mat.get(0, 0));
+        }
+        assert_eq!(3, frames.frames_written());
+        frames.finish().unwrap();
+        let first = fs::read(dir.join("frames").join("000000.ppm")).unwrap();
+        assert_eq!(b"P6\n2 2\n255\n\0\0\0", &first[..14]);
+        assert!(dir.join("frames").join("000002.ppm").exists());
+        assert!(!dir.join("frames").join("000003.ppm").exists());
+
+        let mut gif = Recorder::gif(dir.join("anim.gif"), 2, 5, grey).unwrap();
+        gif.record(&mat).unwrap();
+        gif.record(&mat).unwrap();
+        let wide: Mat<u8> = "010\n".parse().unwrap();
+        assert!(gif.record(&wide).is_err());
+        gif.finish().unwrap();
+        let data = fs::read(dir.join("anim.gif")).unwrap();
+        assert_eq!(b"GIF89a", &data[..6]);
+        assert_eq!(Some(&0x3b), data.last());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,177 @@
+//! Pictures of a `Mat`, through a mapping from elements to colours.
+//!
+//! Images get one `scale` by `scale` square of pixels per element, and
+//! terminal output one coloured character per element.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use errors::*;
+use png;
+use Mat;
+
+/// A 24-bit colour
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+    /// Mixes `self` into `other` by `t`, from 0 for `self` to 1 for `other`
+    pub fn blend(self, other: Rgb, t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (f64::from(a) + (f64::from(b) - f64::from(a)) * t).round() as u8;
+        Rgb(
+            mix(self.0, other.0),
+            mix(self.1, other.1),
+            mix(self.2, other.2),
+        )
+    }
+}
+
+/// The path given after `--image` in command line arguments, if any
+pub fn requested_image(args: &[String]) -> Result<Option<&str>> {
+    match args.iter().skip_while(|arg| *arg != "--image").nth(1) {
+        Some(path) => Ok(Some(path.as_str())),
+        None if args.iter().any(|arg| arg == "--image") => bail!("--image needs a path"),
+        None => Ok(None),
+    }
+}
+
+impl<T: Clone> Mat<T> {
+    /// The pixels of the image, as rows of RGB bytes
+    pub(crate) fn pixels<F>(&self, scale: usize, mut colour: F) -> Vec<u8>
+    where
+        F: FnMut(&T) -> Rgb,
+    {
+        let width = self.cols() * scale;
+        let mut pixels = Vec::with_capacity(3 * width * self.rows() * scale);
+        for row in self.rows_iter() {
+            let start = pixels.len();
+            for element in row {
+                let Rgb(r, g, b) = colour(element);
+                for _ in 0..scale {
+                    pixels.extend_from_slice(&[r, g, b]);
+                }
+            }
+            for _ in 1..scale {
+                pixels.extend_from_within(start..start + 3 * width);
+            }
+        }
+        pixels
+    }
+
+    /// Writes a binary PPM image
+    pub fn write_ppm<W, F>(&self, mut out: W, scale: usize, colour: F) -> Result<()>
+    where
+        W: Write,
+        F: FnMut(&T) -> Rgb,
+    {
+        let (width, height) = (self.cols() * scale, self.rows() * scale);
+        write!(out, "P6\n{} {}\n255\n", width, height)?;
+        out.write_all(&self.pixels(scale, colour))?;
+        Ok(())
+    }
+
+    /// Writes a PNG image
+    pub fn write_png<W, F>(&self, out: W, scale: usize, colour: F) -> Result<()>
+    where
+        W: Write,
+        F: FnMut(&T) -> Rgb,
+    {
+        let (width, height) = (self.cols() * scale, self.rows() * scale);
+        let mut encoder = png::Encoder::new(out, width as u32, height as u32);
+        encoder.set_color(png::ColorType::RGB);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels(scale, colour))?;
+        Ok(())
+    }
+
+    /// Writes a PPM image if `path` ends in `.ppm`, and a PNG image otherwise
+    pub fn save_image<P, F>(&self, path: P, scale: usize, colour: F) -> Result<()>
+    where
+        P: AsRef<Path>,
+        F: FnMut(&T) -> Rgb,
+    {
+        let path = path.as_ref();
+        let file =
+            File::create(path).chain_err(|| format!("unable to create {}", path.display()))?;
+        let mut out = BufWriter::new(file);
+        if path.extension().map_or(false, |ext| ext == "ppm") {
+            self.write_ppm(&mut out, scale, colour)?;
+        } else {
+            self.write_png(&mut out, scale, colour)?;
+        }
+        // dropping `out` would hide a failure to write what it still holds
+        out.flush()
+            .chain_err(|| format!("unable to write {}", path.display()))
+    }
+
+    /// The `to_string_with` rendering, with every character coloured by ANSI
+    /// 24-bit escape codes
+    pub fn to_ansi_string<F>(&self, mut cell: F) -> String
+    where
+        F: FnMut(&T) -> (char, Rgb),
+    {
+        let mut result = String::new();
+        for row in self.rows_iter() {
+            let mut current = None;
+            for element in row {
+                let (c, colour) = cell(element);
+                if current != Some(colour) {
+                    let Rgb(r, g, b) = colour;
+                    result.push_str(&format!("\x1b[38;2;{};{};{}m", r, g, b));
+                    current = Some(colour);
+                }
+                result.push(c);
+            }
+            result.push_str("\x1b[0m\n");
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{requested_image, Rgb};
+    use Mat;
+
+    #[test]
+    fn test_ppm() {
+        let mat: Mat<u8> = "10\n".parse().unwrap();
+        let grey = |&v: &u8| Rgb::BLACK.blend(Rgb::WHITE, f64::from(v));
+        let mut out = Vec::new();
+        mat.write_ppm(&mut out, 2, grey).unwrap();
+        let mut expected = b"P6\n4 2\n255\n".to_vec();
+        for _ in 0..2 {
+            expected.extend_from_slice(&[255; 6]);
+            expected.extend_from_slice(&[0; 6]);
+        }
+        assert_eq!(expected, out);
+
+        let mut out = Vec::new();
+        mat.write_png(&mut out, 2, grey).unwrap();
+        assert_eq!(b"\x89PNG", &out[..4]);
+    }
+
+    #[test]
+    fn test_ansi() {
+        let mat: Mat<u8> = "112\n".parse().unwrap();
+        let cell = |&v: &u8| (char::from(b'0' + v), Rgb(v, 0, 0));
+        assert_eq!(
+            "\x1b[38;2;1;0;0m11\x1b[38;2;2;0;0m2\x1b[0m\n",
+            mat.to_ansi_string(cell)
+        );
+    }
+    #[test]
+    fn test_requested_image() {
+        let args = |args: &[&str]| -> Vec<String> { args.iter().map(|&arg| arg.into()).collect() };
+        let given = args(&["--ansi", "--image", "out.png"]);
+        assert_eq!(Some("out.png"), requested_image(&given).unwrap());
+        assert_eq!(None, requested_image(&args(&["--ansi"])).unwrap());
+        assert!(requested_image(&args(&["--image"])).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+//! Shortest paths over graphs given by a successor function.
+//!
+//! Nodes can be any ordered, hashable value, and their order is used to break
+//! every tie: among goals at the same distance the smallest one ends the
+//! search, and among equally short ways to reach a node the one through the
+//! smallest predecessor is kept. For grid positions as `(row, col)` tuples,
+//! that is reading order.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The outcome of a search
+#[derive(Debug, Clone)]
+pub struct Search<N: Eq + Hash, C = usize> {
+    /// Distance from the start to every node reached. When the search stopped
+    /// at a goal, nodes that were reached but not yet expanded may have a
+    /// shorter distance than recorded.
+    pub distances: HashMap<N, C>,
+    /// The node each reached node was first reached from on a shortest path
+    pub predecessors: HashMap<N, N>,
+    /// The goal that stopped the search, if any
+    pub goal: Option<N>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Search<N, C> {
+    fn new(start: N, zero: C) -> Search<N, C> {
+        let mut distances = HashMap::new();
+        distances.insert(start, zero);
+        Search {
+            distances,
+            predecessors: HashMap::new(),
+            goal: None,
+        }
+    }
+
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).cloned()
+    }
+
+    /// The nodes on the path from the start to `node`, both included
+    pub fn path(&self, node: &N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+        let mut path = vec![node.clone()];
+        while let Some(previous) = self.predecessors.get(path.last().unwrap()) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// The path from the start to the goal that stopped the search
+    pub fn goal_path(&self) -> Option<Vec<N>> {
+        self.goal.as_ref().and_then(|goal| self.path(goal))
+    }
+}
+
+/// Breadth-first search from `start`, where every edge has length 1. Stops at
+/// the smallest goal of the first layer that contains one, or once every
+/// reachable node has been visited.
+pub fn bfs<N, F, I, G>(start: N, mut successors: F, mut is_goal: G) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash + Ord,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool,
+{
+    let mut search = Search::new(start.clone(), 0);
+    let mut layer = vec![start];
+    let mut distance = 0;
+    while !layer.is_empty() {
+        layer.sort();
+        if let Some(goal) = layer.iter().find(|node| is_goal(node)) {
+            search.goal = Some(goal.clone());
+            break;
+        }
+        distance += 1;
+        let mut next = Vec::new();
+        // the layer is sorted, so the first node to reach another is also
+        // the smallest predecessor
+        for node in layer.iter() {
+            for successor in successors(node) {
+                if !search.distances.contains_key(&successor) {
+                    search.distances.insert(successor.clone(), distance);
+                    search.predecessors.insert(successor.clone(), node.clone());
+                    next.push(successor);
+                }
+            }
+        }
+        layer = next;
+    }
+    search
+}
+
+/// Dijkstra's algorithm from `start`, over successors given with the length
+/// of the edge leading to them. Lengths must not be negative. Stops at the
+/// first goal expanded, or once every reachable node has been expanded.
+pub fn dijkstra<N, C, F, I, G>(start: N, successors: F, is_goal: G) -> Search<N, C>
+where
+    N: Clone + Eq + Hash + Ord,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    G: FnMut(&N) -> bool,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// A* search from `start`. `heuristic` estimates the remaining distance to a
+/// goal, and has to be consistent (never decrease by more than the length of
+/// an edge, and be zero at goals) for the distances to be shortest.
+pub fn astar<N, C, F, I, H, G>(
+    start: N,
+    mut successors: F,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash + Ord,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+{
+    let zero = C::default();
+    let mut search = Search::new(start.clone(), zero);
+    let mut expanded = HashSet::new();
+    // binary heaps are max heaps, so we need Reverse values
+    let mut queue = BinaryHeap::new();
+    queue.push(Reverse((heuristic(&start), start, zero)));
+    while let Some(Reverse((_, node, distance))) = queue.pop() {
+        if !expanded.insert(node.clone()) {
+            continue;
+        }
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        for (successor, length) in successors(&node) {
+            let candidate = distance + length;
+            let better = match search.distances.get(&successor) {
+                None => true,
+                Some(&known) if candidate < known => true,
+                Some(&known) => {
+                    if candidate == known
+                        && !expanded.contains(&successor)
+                        && search
+                            .predecessors
+                            .get(&successor)
+                            .map_or(false, |p| node < *p)
+                    {
+                        search.predecessors.insert(successor.clone(), node.clone());
+                    }
+                    false
+                }
+            };
+            if better {
+                search.distances.insert(successor.clone(), candidate);
+                search.predecessors.insert(successor.clone(), node.clone());
+                let estimate = candidate + heuristic(&successor);
+                queue.push(Reverse((estimate, successor, candidate)));
+            }
+        }
+    }
+    search
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{astar, bfs, dijkstra};
+    use Mat;
+
+    fn open(maze: &Mat<char>, &(row, col): &(usize, usize)) -> Vec<(usize, usize)> {
+        maze.neighbors4(row, col)
+            .filter(|&(r, c)| *maze.get(r, c) != '#')
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let maze: Mat<char> = "#######\n#.....#\n#.#.#.#\n#.....#\n#######\n"
+            .parse()
+            .unwrap();
+        // both ways around the pillar are as short, so reading order decides
+        let search = bfs((1, 1), |p| open(&maze, p), |&p| p == (3, 3));
+        assert_eq!(Some((3, 3)), search.goal);
+        assert_eq!(Some(4), search.distance(&(3, 3)));
+        assert_eq!(
+            Some(vec![(1, 1), (1, 2), (1, 3), (2, 3), (3, 3)]),
+            search.goal_path()
+        );
+
+        let all = bfs((1, 1), |p| open(&maze, p), |_| false);
+        assert_eq!(None, all.goal);
+        assert_eq!(13, all.distances.len());
+        assert_eq!(Some(6), all.distance(&(3, 5)));
+        assert_eq!(None, all.path(&(0, 0)));
+    }
+
+    #[test]
+    fn test_weighted() {
+        // a -> b costs 1 and b -> d costs 5, while a -> c -> d costs 2 + 2
+        let edges = |node: &char| match *node {
+            'a' => vec![('b', 1), ('c', 2)],
+            'b' => vec![('d', 5)],
+            'c' => vec![('d', 2)],
+            _ => vec![],
+        };
+        let search = dijkstra('a', edges, |&node| node == 'd');
+        assert_eq!(Some(4), search.distance(&'d'));
+        assert_eq!(Some(vec!['a', 'c', 'd']), search.goal_path());
+
+        let remaining = |node: &char| match *node {
+            'a' => 3,
+            'b' => 4,
+            'c' => 2,
+            _ => 0,
+        };
+        let search = astar('a', edges, remaining, |&node| node == 'd');
+        assert_eq!(Some(vec!['a', 'c', 'd']), search.goal_path());
+        // b is reached, but the heuristic keeps it from being expanded
+        assert_eq!(Some(1), search.distance(&'b'));
+        assert!(!search.predecessors.values().any(|&p| p == 'b'));
+    }
+
+    #[test]
+    fn test_predecessor_ties() {
+        // d can be reached through b or c at the same cost; b is smaller
+        let edges = |node: &char| match *node {
+            'a' => vec![('c', 1), ('b', 1)],
+            'b' | 'c' => vec![('d', 1)],
+            _ => vec![],
+        };
+        let search = dijkstra('a', edges, |_| false);
+        assert_eq!(Some(vec!['a', 'b', 'd']), search.path(&'d'));
+    }
+}
@@ -0,0 +1,112 @@
+//! The interface every day implements, so that one runner can solve any day.
+
+use std::time::{Duration, Instant};
+
+use bench::Allocations;
+use errors::*;
+
+/// A day's puzzle. `parse` reads the puzzle input once, and both parts answer
+/// from what it returns.
+pub trait Solution {
+    type Input;
+
+    fn parse(data: &str) -> Result<Self::Input>;
+
+    fn part_1(input: &Self::Input) -> Result<String>;
+
+    fn part_2(input: &Self::Input) -> Result<String>;
+
+    /// Output besides the answers that the command line asks for, like
+    /// pictures of a simulation. There is none by default.
+    fn extras(_input: &Self::Input, _args: &[String]) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A value with the time it took to compute, and the allocations made on
+/// the way
+#[derive(Debug, Clone)]
+pub struct Timed<T> {
+    pub value: T,
+    pub time: Duration,
+    pub allocations: Allocations,
+}
+
+impl<T> Timed<T> {
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Timed<U> {
+        Timed {
+            value: f(self.value),
+            time: self.time,
+            allocations: self.allocations,
+        }
+    }
+}
+
+impl<T> Timed<Result<T>> {
+    /// The measured value if it was computed, or the error if not
+    pub fn transpose(self) -> Result<Timed<T>> {
+        let value = self.value?;
+        Ok(Timed {
+            value,
+            time: self.time,
+            allocations: self.allocations,
+        })
+    }
+}
+
+pub fn timed<T, F: FnOnce() -> T>(f: F) -> Timed<T> {
+    let allocations = Allocations::so_far();
+    let start = Instant::now();
+    let value = f();
+    Timed {
+        value,
+        time: start.elapsed(),
+        allocations: Allocations::so_far() - allocations,
+    }
+}
+
+/// The answers to both parts of a day, and what parsing took
+#[derive(Debug, Clone)]
+pub struct Answers {
+    pub parse: Timed<()>,
+    pub part_1: Timed<String>,
+    pub part_2: Timed<String>,
+}
+
+/// Parses `data` and answers both parts
+pub fn solve<S: Solution>(data: &str) -> Result<Answers> {
+    let input = timed(|| S::parse(data).chain_err(|| "unable to parse input")).transpose()?;
+    let part_1 =
+        timed(|| S::part_1(&input.value).chain_err(|| "unable to solve part 1")).transpose()?;
+    let part_2 =
+        timed(|| S::part_2(&input.value).chain_err(|| "unable to solve part 2")).transpose()?;
+    Ok(Answers {
+        parse: input.map(|_| ()),
+        part_1,
+        part_2,
+    })
+}
+
+/// Parses `data` and writes the extra output asked for by `args`
+pub fn extras<S: Solution>(data: &str, args: &[String]) -> Result<()> {
+    let input = S::parse(data).chain_err(|| "unable to parse input")?;
+    S::extras(&input, args)
+}
+
+/// A day behind the `Solution` of that day
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub number: usize,
+    pub solve: fn(&str) -> Result<Answers>,
+    pub extras: fn(&str, &[String]) -> Result<()>,
+}
+
+impl Day {
+    pub fn of<S: Solution>(number: usize) -> Day {
+        Day {
+            number,
+            solve: solve::<S>,
+            extras: extras::<S>,
+        }
+    }
+}
@@ -1,2 +1,35 @@
 #!/usr/bin/env bash
-RUST_BACKTRACE=1 cargo test --release --verbose --bin day_$1
+# usage: test.sh [DAYS]
+#
+# Runs the tests of DAYS, a day like 7, a range like 1-10 or all (the
+# default), including the checks against their recorded answers.
+set -e
+
+fail() {
+    echo "test.sh: $1" >&2
+    echo "usage: test.sh [DAYS], where DAYS is a day like 7, a range like 1-10, or all" >&2
+    exit 1
+}
+
+spec=${1:-all}
+filters=()
+if [ "$spec" != all ]; then
+    case "$spec" in
+        *-*) first=${spec%%-*} last=${spec#*-} ;;
+        *) first=$spec last=$spec ;;
+    esac
+    for n in "$first" "$last"; do
+        [[ "$n" =~ ^[0-9]+$ ]] || fail "invalid day '$n'"
+    done
+    first=$((10#$first))
+    last=$((10#$last))
+    if [ "$first" -lt 1 ] || [ "$last" -gt 25 ] || [ "$first" -gt "$last" ]; then
+        fail "there are no days in $spec"
+    fi
+    # day_07 matches the tests in days::day_07 and the day_07 answers test,
+    # and nothing of any other day
+    for ((n = first; n <= last; n++)); do
+        filters+=("$(printf 'day_%02d' "$n")")
+    done
+fi
+RUST_BACKTRACE=1 cargo test --release --verbose -- "${filters[@]}"
@@ -0,0 +1,56 @@
+//! Every day over its input has to give the answers recorded in
+//! `fixtures/day_NN.ans`. Record new answers with
+//! `cargo run --release --bin aoc -- DAYS --save-answers`.
+
+extern crate aoc2018;
+
+use aoc2018::{answers, days};
+use std::path::Path;
+
+fn check(number: usize) {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+    let day = days::get(number).unwrap();
+    if let Err(e) = answers::check(&day, &fixtures) {
+        let causes: Vec<String> = e.iter().map(|cause| cause.to_string()).collect();
+        panic!("{}", causes.join("\ncaused by: "));
+    }
+}
+
+macro_rules! days {
+    ($($name:ident: $number:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                check($number);
+            }
+        )*
+    };
+}
+
+days! {
+    day_01: 1,
+    day_02: 2,
+    day_03: 3,
+    day_04: 4,
+    day_05: 5,
+    day_06: 6,
+    day_07: 7,
+    day_08: 8,
+    day_09: 9,
+    day_10: 10,
+    day_11: 11,
+    day_12: 12,
+    day_13: 13,
+    day_14: 14,
+    day_15: 15,
+    day_16: 16,
+    day_17: 17,
+    day_18: 18,
+    day_19: 19,
+    day_20: 20,
+    day_21: 21,
+    day_22: 22,
+    day_23: 23,
+    day_24: 24,
+    day_25: 25,
+}